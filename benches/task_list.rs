@@ -0,0 +1,102 @@
+// Measures how many TaskList rows are updated when one Task is edited or selected in a list of thousands of Tasks.
+// The rows are built from the same TaskRows the TaskList renders, and a row counts as updated when one of its signals
+// has changed. The DOM is not involved, so the benchmark runs natively with `cargo bench --bench task_list`.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use leptos::prelude::*;
+use leptos_client_todo_app::task_rows::{TaskRow, TaskRows};
+use todo_core::model::{Task, TaskId, TaskStatus};
+
+const TASK_COUNTS: [u32; 3] = [1_000, 5_000, 10_000];
+const ITERATIONS: u32 = 20;
+
+// A rendered row. The Memo reruns only when one of the row's signals has changed, like the effects that update a row in the DOM.
+fn render_row(row : TaskRow, updated_rows : StoredValue<u32>) -> Memo<()> {
+    Memo::new(move |_| {
+        row.task.track();
+        row.is_selected.track();
+        row.is_pending.track();
+        updated_rows.update_value(|count| *count += 1);
+    })
+}
+
+// Applies the change, brings every rendered row up to date, and returns the number of rows that were updated and how long it took
+fn update_rows(rendered_rows : &[Memo<()>], updated_rows : StoredValue<u32>, change : impl FnOnce()) -> (u32, Duration) {
+    updated_rows.set_value(0);
+
+    let start = Instant::now();
+
+    change();
+
+    for rendered_row in rendered_rows {
+        rendered_row.get_untracked();
+    }
+
+    (updated_rows.get_value(), start.elapsed())
+}
+
+fn main() {
+    let owner = Owner::new();
+    owner.set();
+
+    for task_count in TASK_COUNTS {
+        let mut tasks = (1..=task_count).map(|id| Task { id : TaskId(id), name : format!("Task {}", id), ..Task::default() }).collect::<Vec<_>>();
+        let updated_rows = StoredValue::new(0);
+
+        let start = Instant::now();
+
+        let rows = TaskRows::new(None);
+        rows.sync(&tasks.iter().collect::<Vec<_>>());
+        let rendered_rows = rows.ids().get_untracked().into_iter()
+            .filter_map(|task_id| rows.row(task_id))
+            .map(|row| render_row(row, updated_rows))
+            .collect::<Vec<_>>();
+
+        let (rendered_count, _) = update_rows(&rendered_rows, updated_rows, || ());
+        let render_time = start.elapsed();
+
+        assert_eq!(rendered_count, task_count);
+
+        let mut edit_time = Duration::ZERO;
+        let mut select_time = Duration::ZERO;
+        let mut pending_time = Duration::ZERO;
+
+        for iteration in 0..ITERATIONS {
+            let index = (iteration * 7919 % task_count) as usize;
+            let task_id = tasks[index].id;
+
+            // Changing the status of one Task updates only its row
+            let (edited_rows, time) = update_rows(&rendered_rows, updated_rows, || {
+                tasks[index].status = if tasks[index].status == TaskStatus::Complete() { TaskStatus::New() } else { TaskStatus::Complete() };
+                rows.sync(&tasks.iter().collect::<Vec<_>>());
+            });
+
+            assert_eq!(edited_rows, 1);
+            edit_time += time;
+
+            // Selecting a Task updates the row that was selected and the row that is selected
+            let (selected_rows, time) = update_rows(&rendered_rows, updated_rows, || rows.select(Some(task_id)));
+
+            assert!(selected_rows <= 2);
+            select_time += time;
+
+            // Marking a Task as pending, and as saved again, updates only its row
+            let (pending_rows, time) = update_rows(&rendered_rows, updated_rows, || rows.set_pending(HashSet::from([task_id])));
+            let (saved_rows, _) = update_rows(&rendered_rows, updated_rows, || rows.set_pending(HashSet::new()));
+
+            assert_eq!((pending_rows, saved_rows), (1, 1));
+            pending_time += time;
+        }
+
+        println!(
+            "{:>6} tasks: render {:>9.3?}, edit {:>9.3?}, select {:>9.3?}, mark pending {:>9.3?}",
+            task_count,
+            render_time,
+            edit_time / ITERATIONS,
+            select_time / ITERATIONS,
+            pending_time / ITERATIONS
+        );
+    }
+}
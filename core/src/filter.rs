@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Task, TaskPriority, TaskStatus, UserId};
+use crate::time::{TimeZone, Timestamp};
+
+// The due dates a Task can be filtered by. The days are days in the user's time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DueRange {
+    // Due before now and not complete
+    Overdue(),
+    Today(),
+    // Due from Monday to Sunday of the current week
+    ThisWeek(),
+    // Due from the day of from to the end of the day of to. Either end can be left open.
+    Custom { from : Option<Timestamp>, to : Option<Timestamp> }
+}
+
+impl DueRange {
+    // The ranges picked from a list. The Custom range is shown with inputs for its dates.
+    pub const PRESETS: [DueRange; 3] = [Self::Overdue(), Self::Today(), Self::ThisWeek()];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Overdue() => "Overdue",
+            Self::Today() => "Today",
+            Self::ThisWeek() => "This week",
+            Self::Custom { .. } => "Custom",
+        }
+    }
+
+    // Value used to identify the range in a select option
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Overdue() => "overdue",
+            Self::Today() => "today",
+            Self::ThisWeek() => "week",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    // The times a Task must be due from, inclusive, and before, exclusive, to be in the range
+    fn bounds(&self, now : Timestamp, zone : &impl TimeZone) -> (Option<Timestamp>, Option<Timestamp>) {
+        match self {
+            Self::Overdue() => (None, Some(now)),
+            Self::Today() => (Some(now.start_of_day(zone)), Some(now.start_of_day_after(1, zone))),
+            Self::ThisWeek() => {
+                let start_of_week = now.start_of_week(zone);
+                (Some(start_of_week), Some(start_of_week.start_of_day_after(7, zone)))
+            },
+            Self::Custom { from, to } => (from.map(|from| from.start_of_day(zone)), to.map(|to| to.start_of_day_after(1, zone)))
+        }
+    }
+}
+
+// The filters of the TaskList. A Task is listed if it matches every filter that is set. Within a filter, a Task matches if it
+// has any of the picked values, e.g. a Task that is New or Pending, and is assigned to any of the picked users.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskFilter {
+    pub statuses : Vec<TaskStatus>,
+    pub priorities : Vec<TaskPriority>,
+    pub assignees : Vec<UserId>,
+    pub due : Option<DueRange>
+}
+
+impl TaskFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Returns the Tasks that match the filter, in the order they are given. Today and this week are found from now in the time zone.
+    pub fn filter<'a>(&self, tasks : impl IntoIterator<Item = &'a Task>, now : Timestamp, zone : &impl TimeZone) -> Vec<&'a Task> {
+
+        let (due_from, due_before) = self.due.map(|due| due.bounds(now, zone)).unwrap_or_default();
+
+        tasks.into_iter().filter(|task| {
+            (self.statuses.is_empty() || self.statuses.contains(&task.status))
+                && (self.priorities.is_empty() || self.priorities.contains(&task.priority))
+                && (self.assignees.is_empty() || task.assigned_to.iter().any(|user_id| self.assignees.contains(user_id)))
+                && due_from.is_none_or(|due_from| task.due_date >= due_from)
+                && due_before.is_none_or(|due_before| task.due_date < due_before)
+                && (self.due != Some(DueRange::Overdue()) || task.status != TaskStatus::Complete())
+        }).collect()
+    }
+}
+
+// Removes the value if it has been picked, otherwise the value is picked
+pub fn toggle<T : PartialEq>(values : &mut Vec<T>, value : T) {
+    if let Some(index) = values.iter().position(|picked| *picked == value) {
+        values.remove(index);
+    } else {
+        values.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskId;
+    use crate::time::{LocalDateTime, UTC};
+
+    // Wednesday 5 March 2025 at noon
+    fn now() -> Timestamp {
+        Timestamp::from_local(LocalDateTime { year : 2025, month : 3, day : 5, hour : 12, minute : 0 }, &UTC).unwrap()
+    }
+
+    fn task(id : u32, status : TaskStatus, priority : TaskPriority, assigned_to : Vec<u32>, due_date : Timestamp) -> Task {
+        Task {
+            id : TaskId(id),
+            status,
+            priority,
+            assigned_to : assigned_to.into_iter().map(UserId).collect(),
+            due_date,
+            ..Task::default()
+        }
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            task(1, TaskStatus::New(), TaskPriority::High(), vec![1], now().add_hours(-1)),
+            task(2, TaskStatus::Complete(), TaskPriority::High(), vec![2], now().add_days(-1)),
+            task(3, TaskStatus::Pending(), TaskPriority::Low(), vec![1, 2], now().add_hours(6)),
+            task(4, TaskStatus::New(), TaskPriority::Medium(), vec![], now().add_days(3)),
+            task(5, TaskStatus::InProgress(), TaskPriority::High(), vec![3], now().add_days(5)),
+        ]
+    }
+
+    fn filtered_ids(filter : &TaskFilter) -> Vec<u32> {
+        filter.filter(&tasks(), now(), &UTC).into_iter().map(|task| task.id.0).collect()
+    }
+
+    #[test]
+    fn lists_every_task_without_filters() {
+        assert!(TaskFilter::default().is_empty());
+        assert_eq!(filtered_ids(&TaskFilter::default()), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn matches_any_picked_value_and_every_filter() {
+        let filter = TaskFilter { statuses : vec![TaskStatus::New(), TaskStatus::Pending()], ..TaskFilter::default() };
+        assert_eq!(filtered_ids(&filter), vec![1, 3, 4]);
+
+        let filter = TaskFilter { priorities : vec![TaskPriority::High()], assignees : vec![UserId(1), UserId(3)], ..TaskFilter::default() };
+        assert_eq!(filtered_ids(&filter), vec![1, 5]);
+
+        let filter = TaskFilter { statuses : vec![TaskStatus::New()], priorities : vec![TaskPriority::Medium()], ..TaskFilter::default() };
+        assert_eq!(filtered_ids(&filter), vec![4]);
+    }
+
+    #[test]
+    fn filters_by_due_range() {
+        let due = |due| filtered_ids(&TaskFilter { due : Some(due), ..TaskFilter::default() });
+
+        // Task 2 is in the past but complete, so it is not overdue
+        assert_eq!(due(DueRange::Overdue()), vec![1]);
+        assert_eq!(due(DueRange::Today()), vec![1, 3]);
+        // The week runs from Monday 3 March to Sunday 9 March
+        assert_eq!(due(DueRange::ThisWeek()), vec![1, 2, 3, 4]);
+        assert_eq!(due(DueRange::Custom { from : Some(now().add_days(3)), to : None }), vec![4, 5]);
+        assert_eq!(due(DueRange::Custom { from : Some(now().add_days(-1)), to : Some(now()) }), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn toggles_picked_values() {
+        let mut statuses = vec![TaskStatus::New()];
+
+        toggle(&mut statuses, TaskStatus::Pending());
+        toggle(&mut statuses, TaskStatus::New());
+
+        assert_eq!(statuses, vec![TaskStatus::Pending()]);
+    }
+}
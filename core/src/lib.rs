@@ -0,0 +1,15 @@
+// The data model and logic of the task app that do not depend on the browser, so they can be tested with cargo test
+// and shared by the Leptos client and the server.
+
+pub mod filter;
+pub mod model;
+pub mod panel;
+pub mod search;
+pub mod seed;
+pub mod sort;
+pub mod stored;
+pub mod tasks;
+pub mod time;
+pub mod validation;
+pub mod views;
+pub mod virtual_list;
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::Timestamp;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub id : TaskId,
+    pub name : String,
+    pub description : String,
+    // The ids of the assigned Users
+    pub assigned_to : Vec<UserId>,
+    pub due_date : Timestamp,
+    pub priority : TaskPriority,
+    pub status : TaskStatus,
+    pub comments : Vec<Comment>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    // Identifies the comment within its Task
+    pub id : u32,
+    // The id of the User who posted the comment. The user's name and avatar are found in the UserDirectory when the comment is rendered.
+    pub user_id : UserId,
+    pub message : String,
+    pub created_at : Timestamp,
+    // Set when the message is edited
+    pub edited_at : Option<Timestamp>
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    New(),
+    Pending(),
+    InProgress(),
+    Complete(),
+}
+
+impl Default for TaskStatus {
+    fn default() -> Self {
+        Self::New()
+    }
+}
+
+impl TaskStatus {
+    // All the statuses in the order a Task moves through them
+    pub const ALL: [TaskStatus; 4] = [Self::New(), Self::Pending(), Self::InProgress(), Self::Complete()];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::New() => "New",
+            Self::Pending() => "Pending",
+            Self::InProgress() => "In Progress",
+            Self::Complete() => "Complete",
+        }
+    }
+
+    // Value used to identify the status in a select option
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::New() => "new",
+            Self::Pending() => "pending",
+            Self::InProgress() => "inprogress",
+            Self::Complete() => "complete",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::New() => "task-status-new",
+            Self::Pending() => "task-status-pending",
+            Self::InProgress() => "task-status-inprogress",
+            Self::Complete() => "task-status-complete",
+        }
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = ();
+
+    fn from_str(key : &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|status| status.key() == key).ok_or(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TaskPriority {
+    Low(),
+    Medium(),
+    High()
+}
+
+impl Default for TaskPriority {
+    fn default() -> Self {
+        Self::Low()
+    }
+}
+
+impl TaskPriority {
+    pub const ALL: [TaskPriority; 3] = [Self::Low(), Self::Medium(), Self::High()];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low() => "Low",
+            Self::Medium() => "Medium",
+            Self::High() => "High",
+        }
+    }
+
+    // Value used to identify the priority in a select option
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Low() => "low",
+            Self::Medium() => "medium",
+            Self::High() => "high",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Low() => "task-priority-low",
+            Self::Medium() => "task-priority-medium",
+            Self::High() => "task-priority-high",
+        }
+    }
+}
+
+impl FromStr for TaskPriority {
+    type Err = ();
+
+    fn from_str(key : &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|priority| priority.key() == key).ok_or(())
+    }
+}
+
+// The unique id of a Task, shown as a short key such as TASK-42
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TaskId(pub u32);
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TASK-{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UserId(pub u32);
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id : UserId,
+    pub name : String,
+    pub image : String
+}
+
+// The Users that Tasks and comments reference by id. The name and avatar of a user are looked up here when they are rendered,
+// so that a user is shown the same way everywhere.
+#[derive(Clone, Default, PartialEq)]
+pub struct UserDirectory {
+    users : BTreeMap<UserId, User>
+}
+
+impl UserDirectory {
+    pub fn new(users : Vec<User>) -> Self {
+        Self { users: users.into_iter().map(|user| (user.id, user)).collect() }
+    }
+
+    pub fn get(&self, user_id : UserId) -> Option<&User> {
+        self.users.get(&user_id)
+    }
+
+    // The users in the order of their ids
+    pub fn iter(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    // Returns the name of the user, or a placeholder if the user is not in the directory
+    pub fn name(&self, user_id : UserId) -> String {
+        self.get(user_id).map(|user| user.name.clone()).unwrap_or_else(|| "Unknown user".to_string())
+    }
+
+    // Returns the path of the user's avatar, or an empty path if the user is not in the directory
+    pub fn avatar(&self, user_id : UserId) -> String {
+        self.get(user_id).map(|user| format!("/static/{}", user.image)).unwrap_or_default()
+    }
+
+    // Finds the id of the user with the given name
+    pub fn find_by_name(&self, name : &str) -> Option<UserId> {
+        self.iter().find(|user| user.name == name).map(|user| user.id)
+    }
+}
+
+// A Task that has been moved to the trash, along with the time it was deleted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashedTask {
+    pub task : Task,
+    pub deleted_at : Timestamp
+}
+
+impl TrashedTask {
+    // The time the Task is purged from the trash, when Tasks are kept for the given number of days
+    pub fn purge_date(&self, retention_days : u32) -> Timestamp {
+        self.deleted_at.add_days(retention_days as i32)
+    }
+}
+
+// Number of days a Task is kept in the trash before it is purged, unless changed in the TrashList component
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_ids_are_shown_as_keys() {
+        assert_eq!(TaskId(42).to_string(), "TASK-42");
+    }
+
+    #[test]
+    fn looks_up_users_in_the_directory() {
+        let users = UserDirectory::new(crate::seed::get_users());
+
+        assert_eq!(users.name(UserId(3)), "John");
+        assert_eq!(users.avatar(UserId(3)), "/static/person3.png");
+        assert_eq!(users.name(UserId(99)), "Unknown user");
+        assert_eq!(users.find_by_name("Ilyana"), Some(UserId(4)));
+    }
+}
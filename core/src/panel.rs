@@ -0,0 +1,75 @@
+use crate::model::TaskId;
+
+// The segments of the panel paths. The routes of the client's TaskApp component are built from the same segments, so that
+// every path read by from_path has a route.
+pub const TASKS_SEGMENT : &str = "tasks";
+pub const NEW_TASK_SEGMENT : &str = "new";
+pub const EDIT_TASK_SEGMENT : &str = "edit";
+pub const TRASH_SEGMENT : &str = "trash";
+
+// The name of the route parameter that holds the id of the Task in the panel
+pub const TASK_ID_PARAM : &str = "id";
+
+// The panel rendered next to the TaskList. Only one panel is rendered at a time, and each panel has its own URL.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Panel {
+    Empty(),
+    Details(TaskId),
+    NewTask(),
+    EditTask(TaskId),
+    Trash(),
+    NotFound()
+}
+
+impl Panel {
+    // The path of the panel's route
+    pub fn path(&self) -> String {
+        match self {
+            Self::Empty() | Self::NotFound() => format!("/{}", TASKS_SEGMENT),
+            Self::Details(task_id) => format!("/{}/{}", TASKS_SEGMENT, task_id.0),
+            Self::NewTask() => format!("/{}/{}", TASKS_SEGMENT, NEW_TASK_SEGMENT),
+            Self::EditTask(task_id) => format!("/{}/{}/{}", TASKS_SEGMENT, task_id.0, EDIT_TASK_SEGMENT),
+            Self::Trash() => format!("/{}", TRASH_SEGMENT),
+        }
+    }
+
+    // Reads the panel from the path of a URL. Every path returned by path is read back as the same panel, except that
+    // the NotFound panel has no path of its own. A path that matches no route is read as the NotFound panel.
+    pub fn from_path(path : &str) -> Self {
+        let segments = path.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+        let task_id = |id : &str| id.parse().ok().map(TaskId);
+
+        match segments.as_slice() {
+            [] | [TASKS_SEGMENT] => Self::Empty(),
+            [TASKS_SEGMENT, NEW_TASK_SEGMENT] => Self::NewTask(),
+            [TASKS_SEGMENT, id] => task_id(id).map(Self::Details).unwrap_or(Self::NotFound()),
+            [TASKS_SEGMENT, id, EDIT_TASK_SEGMENT] => task_id(id).map(Self::EditTask).unwrap_or(Self::NotFound()),
+            [TRASH_SEGMENT] => Self::Trash(),
+            _ => Self::NotFound()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_panel_from_its_path() {
+        let panels = [Panel::Empty(), Panel::Details(TaskId(4)), Panel::NewTask(), Panel::EditTask(TaskId(12)), Panel::Trash()];
+
+        for panel in panels {
+            assert_eq!(Panel::from_path(&panel.path()), panel);
+        }
+    }
+
+    #[test]
+    fn paths_without_a_route_are_not_found() {
+        assert_eq!(Panel::from_path("/"), Panel::Empty());
+        assert_eq!(Panel::from_path("/tasks/4/"), Panel::Details(TaskId(4)));
+
+        for path in ["/tasks/abc", "/tasks/4/comments", "/tasks/new/edit", "/settings"] {
+            assert_eq!(Panel::from_path(path), Panel::NotFound(), "{}", path);
+        }
+    }
+}
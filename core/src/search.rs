@@ -0,0 +1,424 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use crate::model::{Task, TaskId};
+
+// How much a match counts towards the rank of a Task, by the part of the Task it is found in
+const NAME_WEIGHT: f64 = 3.0;
+const DESCRIPTION_WEIGHT: f64 = 1.5;
+const COMMENT_WEIGHT: f64 = 1.0;
+
+// The number of characters a snippet shows around the first match
+const SNIPPET_LENGTH: usize = 60;
+
+// The words of a search, lowercased. A word matches a word of a Task if they are the same, if the Task's word starts with it,
+// or if they differ by a typo: one edit for words of 4 to 7 letters and two edits for longer words. A letter that is added,
+// removed, replaced, or swapped with the next letter is an edit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    terms : Vec<String>
+}
+
+impl SearchQuery {
+    pub fn new(query : &str) -> Self {
+        Self { terms: words(query).map(|(_, word)| word).collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    // The byte ranges of the words in the text that match the query, for highlighting them
+    pub fn highlights(&self, text : &str) -> Vec<Range<usize>> {
+        words(text)
+            .filter(|(_, word)| self.terms.iter().any(|term| match_quality(term, word).is_some()))
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    // A short part of the description or a comment of the Task around the first match, for showing why a Task was found
+    // when the match is not in its name
+    pub fn snippet(&self, task : &Task) -> Option<Highlighted> {
+        std::iter::once(task.description.as_str())
+            .chain(task.comments.iter().map(|comment| comment.message.as_str()))
+            .find_map(|text| self.text_snippet(text))
+    }
+
+    fn text_snippet(&self, text : &str) -> Option<Highlighted> {
+        let highlights = self.highlights(text);
+        let first_match = highlights.first()?.start;
+
+        // A match near the start of the text is shown from the start, otherwise the snippet starts a few words before the match
+        let start = if text[..first_match].chars().count() <= SNIPPET_LENGTH / 2 {
+            0
+        } else {
+            let start = text[..first_match].char_indices().rev().nth(SNIPPET_LENGTH / 4).map(|(index, _)| index).unwrap_or(0);
+            text[start..first_match].find(char::is_whitespace).map(|space| start + space + 1).unwrap_or(start)
+        };
+        let end = text[start..].char_indices().nth(SNIPPET_LENGTH).map(|(index, _)| start + index).unwrap_or(text.len());
+
+        let prefix = if start > 0 { "\u{2026}" } else { "" };
+        let suffix = if end < text.len() { "\u{2026}" } else { "" };
+
+        Some(Highlighted {
+            text : format!("{}{}{}", prefix, &text[start..end], suffix),
+            ranges : highlights.into_iter()
+                .filter(|range| range.start >= start && range.end <= end)
+                .map(|range| range.start - start + prefix.len()..range.end - start + prefix.len())
+                .collect()
+        })
+    }
+}
+
+// Text with the byte ranges of the matches to highlight
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Highlighted {
+    pub text : String,
+    pub ranges : Vec<Range<usize>>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchResult {
+    pub task_id : TaskId,
+    pub score : f64
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Field {
+    Name(),
+    Description(),
+    Comment()
+}
+
+impl Field {
+    fn weight(&self) -> f64 {
+        match self {
+            Self::Name() => NAME_WEIGHT,
+            Self::Description() => DESCRIPTION_WEIGHT,
+            Self::Comment() => COMMENT_WEIGHT,
+        }
+    }
+}
+
+// A Task's occurrences of a word, in one part of the Task
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Posting {
+    task_id : TaskId,
+    field : Field,
+    count : usize
+}
+
+// The text of a Task as it was indexed, to find the Tasks that have changed since
+#[derive(Clone, Debug, PartialEq)]
+struct Document {
+    name : String,
+    description : String,
+    comments : Vec<String>,
+    words : Vec<String>
+}
+
+// An in-memory index of the words in the names, descriptions and comments of the Tasks
+#[derive(Clone, Debug, Default)]
+pub struct SearchIndex {
+    documents : HashMap<TaskId, Document>,
+    postings : BTreeMap<String, Vec<Posting>>
+}
+
+impl SearchIndex {
+    pub fn new(tasks : &[Task]) -> Self {
+        let mut index = Self::default();
+        index.sync(tasks);
+        index
+    }
+
+    // Brings the index up to date with the Tasks. Only the Tasks that have been added or whose text has changed are indexed again.
+    pub fn sync(&mut self, tasks : &[Task]) {
+        for task in tasks {
+            let is_indexed = self.documents.get(&task.id).is_some_and(|document| {
+                document.name == task.name
+                    && document.description == task.description
+                    && document.comments.iter().eq(task.comments.iter().map(|comment| &comment.message))
+            });
+
+            if !is_indexed {
+                self.insert(task);
+            }
+        }
+
+        if self.documents.len() != tasks.len() {
+            let task_ids = tasks.iter().map(|task| task.id).collect::<Vec<_>>();
+            let removed_task_ids = self.documents.keys().copied().filter(|task_id| !task_ids.contains(task_id)).collect::<Vec<_>>();
+
+            for task_id in removed_task_ids {
+                self.remove(task_id);
+            }
+        }
+    }
+
+    fn insert(&mut self, task : &Task) {
+        self.remove(task.id);
+
+        let fields = std::iter::once((Field::Name(), task.name.as_str()))
+            .chain(std::iter::once((Field::Description(), task.description.as_str())))
+            .chain(task.comments.iter().map(|comment| (Field::Comment(), comment.message.as_str())));
+
+        let mut counts = BTreeMap::<(String, usize), (Field, usize)>::new();
+
+        for (field, text) in fields {
+            for (_, word) in words(text) {
+                counts.entry((word, field as usize)).or_insert((field, 0)).1 += 1;
+            }
+        }
+
+        let mut document_words = Vec::new();
+
+        for ((word, _), (field, count)) in counts {
+            self.postings.entry(word.clone()).or_default().push(Posting { task_id: task.id, field, count });
+
+            if document_words.last() != Some(&word) {
+                document_words.push(word);
+            }
+        }
+
+        self.documents.insert(task.id, Document {
+            name : task.name.clone(),
+            description : task.description.clone(),
+            comments : task.comments.iter().map(|comment| comment.message.clone()).collect(),
+            words : document_words
+        });
+    }
+
+    fn remove(&mut self, task_id : TaskId) {
+        let Some(document) = self.documents.remove(&task_id) else {
+            return;
+        };
+
+        for word in document.words {
+            if let Some(postings) = self.postings.get_mut(&word) {
+                postings.retain(|posting| posting.task_id != task_id);
+
+                if postings.is_empty() {
+                    self.postings.remove(&word);
+                }
+            }
+        }
+    }
+
+    // Returns the Tasks that match every word of the query, the best match first. A word counts by how closely it matches
+    // and by the part of the Task it is found in, a match in the name counts more than a match in a comment.
+    // Tasks with the same score are listed in the order of their ids.
+    pub fn search(&self, query : &SearchQuery) -> Vec<SearchResult> {
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores = HashMap::<TaskId, (usize, f64)>::new();
+
+        for term in &query.terms {
+            let mut term_scores = HashMap::<TaskId, f64>::new();
+
+            for (word, postings) in &self.postings {
+                let Some(quality) = match_quality(term, word) else {
+                    continue;
+                };
+
+                for posting in postings {
+                    // A word that occurs several times counts a little more, up to a limit
+                    let score = quality * posting.field.weight() * (1.0 + 0.1 * posting.count.min(5) as f64);
+                    let best_score = term_scores.entry(posting.task_id).or_default();
+                    *best_score = best_score.max(score);
+                }
+            }
+
+            for (task_id, score) in term_scores {
+                let task_score = scores.entry(task_id).or_default();
+                task_score.0 += 1;
+                task_score.1 += score;
+            }
+        }
+
+        let mut results = scores.into_iter()
+            .filter(|(_, (matched_terms, _))| *matched_terms == query.terms.len())
+            .map(|(task_id, (_, score))| SearchResult { task_id, score })
+            .collect::<Vec<_>>();
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.task_id.cmp(&b.task_id)));
+        results
+    }
+}
+
+// Splits the text into lowercase words of letters and digits, with the byte range of each word in the text
+fn words(text : &str) -> impl Iterator<Item = (Range<usize>, String)> + '_ {
+    let mut chars = text.char_indices().peekable();
+
+    std::iter::from_fn(move || {
+        while chars.peek().is_some_and(|(_, c)| !c.is_alphanumeric()) {
+            chars.next();
+        }
+
+        let (start, _) = *chars.peek()?;
+        let mut end = start;
+        let mut word = String::new();
+
+        while let Some((index, c)) = chars.next_if(|(_, c)| c.is_alphanumeric()) {
+            word.extend(c.to_lowercase());
+            end = index + c.len_utf8();
+        }
+
+        Some((start..end, word))
+    })
+}
+
+// How closely the word matches the search term, from 1.0 for the same word, or None if it does not match
+fn match_quality(term : &str, word : &str) -> Option<f64> {
+
+    if term == word {
+        return Some(1.0);
+    }
+
+    let term_length = term.chars().count();
+
+    // Words being typed match the words they start, once they are two letters long
+    if term_length >= 2 && word.starts_with(term) {
+        return Some(0.8);
+    }
+
+    let allowed_edits = match term_length {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2
+    };
+
+    if allowed_edits == 0 || word.chars().count().abs_diff(term_length) > allowed_edits {
+        return None;
+    }
+
+    match edit_distance(term, word) {
+        1 if allowed_edits >= 1 => Some(0.6),
+        2 if allowed_edits >= 2 => Some(0.4),
+        _ => None
+    }
+}
+
+// The number of letters that have to be added, removed, replaced or swapped with the next letter to turn one word into the other
+fn edit_distance(a : &str, b : &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // Three rows of the distance table, the row before the previous one is needed for swapped letters
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Comment, UserId};
+    use crate::time::Timestamp;
+
+    fn task(id : u32, name : &str, description : &str, comments : &[&str]) -> Task {
+        Task {
+            id : TaskId(id),
+            name : name.to_string(),
+            description : description.to_string(),
+            comments : comments.iter().enumerate().map(|(index, message)| Comment {
+                id : index as u32 + 1,
+                user_id : UserId(1),
+                message : message.to_string(),
+                created_at : Timestamp(0.0),
+                edited_at : None
+            }).collect(),
+            ..Task::default()
+        }
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            task(1, "Design login screen", "Email and password fields with validation", &[]),
+            task(2, "Payment gateway", "Integrate Stripe for checkout", &["The login token expires too early"]),
+            task(3, "Login rate limiting", "Block repeated failed attempts", &[]),
+        ]
+    }
+
+    fn search(index : &SearchIndex, query : &str) -> Vec<u32> {
+        index.search(&SearchQuery::new(query)).into_iter().map(|result| result.task_id.0).collect()
+    }
+
+    #[test]
+    fn ranks_matches_in_names_above_matches_in_comments() {
+        let index = SearchIndex::new(&tasks());
+
+        assert_eq!(search(&index, "login"), vec![1, 3, 2]);
+        assert_eq!(search(&index, "stripe"), vec![2]);
+        assert_eq!(search(&index, ""), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn matches_every_word_of_the_query() {
+        let index = SearchIndex::new(&tasks());
+
+        assert_eq!(search(&index, "login token"), vec![2]);
+        assert_eq!(search(&index, "login stripe"), vec![2]);
+        assert_eq!(search(&index, "login missing"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn tolerates_typos_and_partial_words() {
+        let index = SearchIndex::new(&tasks());
+
+        assert_eq!(search(&index, "pasword"), vec![1]);
+        assert_eq!(search(&index, "gatewya"), vec![2]);
+        assert_eq!(search(&index, "valid"), vec![1]);
+        assert_eq!(search(&index, "chekcout"), vec![2]);
+        // Short words must match exactly or be the start of a word
+        assert_eq!(search(&index, "fo"), vec![2]);
+        assert_eq!(search(&index, "fir"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn indexes_changed_tasks_again() {
+        let mut tasks = tasks();
+        let mut index = SearchIndex::new(&tasks);
+
+        tasks[0].name = "Design signup screen".to_string();
+        tasks.remove(2);
+        index.sync(&tasks);
+
+        assert_eq!(search(&index, "login"), vec![2]);
+        assert_eq!(search(&index, "signup"), vec![1]);
+    }
+
+    #[test]
+    fn highlights_matching_words() {
+        let query = SearchQuery::new("Login pasword");
+
+        assert_eq!(query.highlights("Design LOGIN screen, password"), vec![7..12, 21..29]);
+
+        let snippet = query.snippet(&task(1, "", "A long description that mentions the login screen somewhere after the first sixty characters", &[])).unwrap();
+
+        assert!(snippet.text.starts_with('\u{2026}'));
+        assert_eq!(snippet.ranges.iter().map(|range| &snippet.text[range.clone()]).collect::<Vec<_>>(), vec!["login"]);
+        assert_eq!(query.snippet(&task(1, "Login", "No match here", &["A comment about the login"])).map(|snippet| snippet.text), Some("A comment about the login".to_string()));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::model::{Comment, Task, TaskId, TaskPriority, TaskStatus, User, UserId};
+use crate::time::Timestamp;
+
+// The data the app starts with. It is served by the client's LocalDataSource, and the server seeds its database with it.
+
+// The Users the app starts with. They are also used to resolve the assigned users of Tasks stored before users were referenced by id.
+pub fn get_users() -> Vec<User> {
+    vec![
+        User {
+            id : UserId(1),
+            name : "Derik".to_string(),
+            image : "person1.png".to_string(),
+        },
+        User {
+            id : UserId(2),
+            name : "Fatima".to_string(),
+            image : "person2.png".to_string(),
+        },
+        User {
+            id : UserId(3),
+            name : "John".to_string(),
+            image : "person3.png".to_string(),
+        },
+        User {
+            id : UserId(4),
+            name : "Ilyana".to_string(),
+            image : "person4.png".to_string(),
+        },
+    ]
+}
+
+// The Tasks the app starts with. Due dates and comment dates are relative to now.
+pub fn get_tasks(now : Timestamp) -> Vec<Task> {
+    vec![
+        Task {
+            id : TaskId(1),
+            name : "Design login screen".to_string(),
+            description : "Create a responsive login screen with email and password fields, 'Forgot Password' link, and a login button. Include basic validation and error handling.".to_string(),
+            due_date : now.add_days(5),
+            assigned_to : vec![ 
+                UserId(1)
+            ],
+            priority : TaskPriority::Medium(),
+            status : TaskStatus::Pending(),
+            comments : vec![]
+        },
+        Task {
+            id : TaskId(2),
+            name : "Write unit tests for task API".to_string(),
+            description : "Create unit tests for the task-related API endpoints, including task creation, status updates, and deletion. Use mock data and ensure edge cases are covered.".to_string(),
+            due_date : now.add_days(25),
+            assigned_to : vec![ 
+                UserId(1),
+                UserId(2),
+                UserId(3),
+            ],
+            priority : TaskPriority::High(),
+            status : TaskStatus::InProgress(),
+            comments : vec![]
+        },
+        Task {
+            id : TaskId(3),
+            name : "Implement product search with filters".to_string(),
+            description : "Develop a product search feature that allows users to search by name, category, and price range. Include filter options such as 'In Stock', 'On Sale', and 'Free Shipping'. 
+                Ensure the results update dynamically as filters are applied.".to_string(),
+            due_date : now.add_days(45),
+            assigned_to : vec![ 
+                UserId(1),
+                UserId(4)
+            ],
+            priority : TaskPriority::Low(),
+            status : TaskStatus::New(),
+            comments : vec![]
+        },
+        Task {
+            id : TaskId(4),
+            name : "Integrate payment gateway".to_string(),
+            description : "Set up and integrate a payment gateway (e.g., Stripe or PayPal) to handle secure transactions during checkout. Implement payment validation, error handling, 
+                and confirmation messaging. Ensure the system can handle both test and live environments.".to_string(),
+            due_date : now.add_days(10),
+            assigned_to : vec![ 
+                UserId(1),
+            ],
+            priority : TaskPriority::Medium(),
+            status : TaskStatus::Complete(),
+            comments : vec![]
+        },
+        Task {
+            id : TaskId(5),
+            name : "Create order history page".to_string(),
+            description : "Build a user-facing order history page that displays past purchases with order details, statuses, and tracking information. Include pagination and filtering by date or status.".to_string(),
+            due_date : now,
+            assigned_to : vec![ 
+                UserId(4),
+            ],
+            priority : TaskPriority::High(),
+            status : TaskStatus::Pending(),
+            comments : vec![]
+        },
+        Task {
+            id : TaskId(6),
+            name : "Implement product review system".to_string(),
+            description : "Allow users to leave reviews and ratings on products. Design the UI for submitting and displaying reviews, and create backend endpoints to store and fetch review data. 
+                Include moderation capabilities to filter inappropriate content.".to_string(),
+            due_date : now.add_days(12),
+            assigned_to : vec![ 
+                UserId(3),
+                UserId(4)
+            ],
+            priority : TaskPriority::Low(),
+            status : TaskStatus::InProgress(),
+            comments : vec![
+                Comment{
+                    id : 1,
+                    user_id : UserId(3),
+                    message : "Should reviews be visible to everyone immediately, or only after moderation?".to_string(),
+                    created_at : now.add_days(-2),
+                    edited_at : None
+                },Comment{
+                    id : 2,
+                    user_id : UserId(4),
+                    message : "Good question, I'll get back to you".to_string(),
+                    created_at : now.add_days(-1),
+                    edited_at : None
+                },
+                Comment{
+                    id : 3,
+                    user_id : UserId(3),
+                    message : "Ok, thanks, I'll wait for your reply".to_string(),
+                    created_at : now.add_hours(-3),
+                    edited_at : None
+                }
+            ]
+        },
+        Task {
+            id : TaskId(7),
+            name : "Add wishlist functionality".to_string(),
+            description : "Enable users to add products to a personal wishlist for future reference. Implement the UI for adding/removing items and a wishlist page to view saved products. 
+                Ensure the wishlist is saved per user and persists across sessions.".to_string(),
+            due_date : now.add_days(45),
+            assigned_to : vec![ 
+                UserId(1)
+            ],
+            priority : TaskPriority::High(),
+            status : TaskStatus::InProgress(),
+            comments : vec![]
+        }
+    ]
+}
@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Task, TaskPriority, TaskStatus};
+
+// The columns of the TaskList that Tasks can be sorted by
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SortColumn {
+    Id(),
+    Priority(),
+    Name(),
+    AssigneeCount(),
+    DueDate(),
+    Status()
+}
+
+impl SortColumn {
+    // The columns in the order they are shown in the TaskList
+    pub const ALL: [SortColumn; 6] = [Self::Id(), Self::Priority(), Self::Name(), Self::AssigneeCount(), Self::DueDate(), Self::Status()];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Id() => "Id",
+            Self::Priority() => "Priority",
+            Self::Name() => "Name",
+            Self::AssigneeCount() => "Assignees",
+            Self::DueDate() => "Due Date",
+            Self::Status() => "Status",
+        }
+    }
+
+    // Value used to identify the column, e.g. in the TaskList header
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Id() => "id",
+            Self::Priority() => "priority",
+            Self::Name() => "name",
+            Self::AssigneeCount() => "assignees",
+            Self::DueDate() => "due",
+            Self::Status() => "status",
+        }
+    }
+
+    fn compare(&self, a : &Task, b : &Task) -> Ordering {
+        match self {
+            Self::Id() => a.id.cmp(&b.id),
+            Self::Priority() => priority_rank(&a.priority).cmp(&priority_rank(&b.priority)),
+            // Names are compared without case, so that "api" and "API" are listed together
+            Self::Name() => a.name.chars().flat_map(char::to_lowercase).cmp(b.name.chars().flat_map(char::to_lowercase)),
+            Self::AssigneeCount() => a.assigned_to.len().cmp(&b.assigned_to.len()),
+            Self::DueDate() => a.due_date.total_cmp(&b.due_date),
+            Self::Status() => status_rank(&a.status).cmp(&status_rank(&b.status)),
+        }
+    }
+}
+
+impl FromStr for SortColumn {
+    type Err = ();
+
+    fn from_str(key : &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|column| column.key() == key).ok_or(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SortDirection {
+    Ascending(),
+    Descending()
+}
+
+impl SortDirection {
+    pub fn reversed(&self) -> Self {
+        match self {
+            Self::Ascending() => Self::Descending(),
+            Self::Descending() => Self::Ascending(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column : SortColumn,
+    pub direction : SortDirection
+}
+
+// The order the TaskList is sorted in. Tasks are compared by the first key, and Tasks that are equal by it are compared by
+// the next key, e.g. by priority and then by due date. Tasks that are equal by every key keep the order of the list,
+// which is the order of their ids. Without keys the Tasks are listed in the order of their ids.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskSort {
+    pub keys : Vec<SortKey>
+}
+
+impl TaskSort {
+    pub fn key(&self, column : SortColumn) -> Option<(usize, SortKey)> {
+        self.keys.iter().copied().enumerate().find(|(_, key)| key.column == column)
+    }
+
+    // Sorts by the column when its header is clicked. Clicking the column the Tasks are sorted by reverses the direction,
+    // and clicking another column sorts by it alone in ascending order.
+    pub fn sort_by(&mut self, column : SortColumn) {
+        let direction = match self.keys.as_slice() {
+            [key] if key.column == column => key.direction.reversed(),
+            _ => SortDirection::Ascending()
+        };
+
+        self.keys = vec![SortKey { column, direction }];
+    }
+
+    // Adds the column as the next key when its header is shift-clicked. If the Tasks are already sorted by the column,
+    // its direction is reversed and it keeps its place among the keys.
+    pub fn then_by(&mut self, column : SortColumn) {
+        match self.keys.iter_mut().find(|key| key.column == column) {
+            Some(key) => key.direction = key.direction.reversed(),
+            None => self.keys.push(SortKey { column, direction: SortDirection::Ascending() })
+        }
+    }
+
+    pub fn compare(&self, a : &Task, b : &Task) -> Ordering {
+        self.keys.iter().fold(Ordering::Equal, |ordering, key| {
+            ordering.then_with(|| match key.direction {
+                SortDirection::Ascending() => key.column.compare(a, b),
+                SortDirection::Descending() => key.column.compare(b, a),
+            })
+        })
+    }
+
+    // Returns the Tasks in sorted order. The sort is stable, so Tasks that are equal by every key keep their order.
+    pub fn sort<'a>(&self, tasks : impl IntoIterator<Item = &'a Task>) -> Vec<&'a Task> {
+        let mut sorted_tasks = tasks.into_iter().collect::<Vec<_>>();
+
+        if !self.keys.is_empty() {
+            sorted_tasks.sort_by(|a, b| self.compare(a, b));
+        }
+
+        sorted_tasks
+    }
+}
+
+fn priority_rank(priority : &TaskPriority) -> usize {
+    TaskPriority::ALL.iter().position(|p| p == priority).unwrap_or_default()
+}
+
+fn status_rank(status : &TaskStatus) -> usize {
+    TaskStatus::ALL.iter().position(|s| s == status).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{TaskId, UserId};
+    use crate::time::Timestamp;
+
+    fn task(id : u32, name : &str, priority : TaskPriority, due_day : i32) -> Task {
+        Task { id : TaskId(id), name : name.to_string(), priority, due_date : Timestamp(0.0).add_days(due_day), ..Task::default() }
+    }
+
+    fn sorted_ids(sort : &TaskSort, tasks : &[Task]) -> Vec<u32> {
+        sort.sort(tasks).into_iter().map(|task| task.id.0).collect()
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            task(1, "b", TaskPriority::Low(), 3),
+            task(2, "A", TaskPriority::High(), 5),
+            task(3, "c", TaskPriority::High(), 1),
+            task(4, "a", TaskPriority::Low(), 3),
+        ]
+    }
+
+    #[test]
+    fn lists_tasks_by_id_without_keys() {
+        assert_eq!(sorted_ids(&TaskSort::default(), &tasks()), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn sorts_by_several_keys_and_keeps_the_order_of_equal_tasks() {
+        let mut sort = TaskSort::default();
+
+        sort.sort_by(SortColumn::Priority());
+        sort.sort_by(SortColumn::Priority());
+        sort.then_by(SortColumn::DueDate());
+
+        // High priority first, then by due date. Tasks 1 and 4 are equal by both keys and keep their order.
+        assert_eq!(sorted_ids(&sort, &tasks()), vec![3, 2, 1, 4]);
+
+        sort.then_by(SortColumn::DueDate());
+        assert_eq!(sorted_ids(&sort, &tasks()), vec![2, 3, 1, 4]);
+    }
+
+    #[test]
+    fn compares_names_without_case_and_counts_assignees() {
+        let mut sort = TaskSort::default();
+
+        sort.sort_by(SortColumn::Name());
+        assert_eq!(sorted_ids(&sort, &tasks()), vec![2, 4, 1, 3]);
+
+        let mut tasks = tasks();
+        tasks[0].assigned_to = vec![UserId(1), UserId(2)];
+        tasks[2].assigned_to = vec![UserId(1)];
+
+        sort.sort_by(SortColumn::AssigneeCount());
+        sort.sort_by(SortColumn::AssigneeCount());
+        assert_eq!(sorted_ids(&sort, &tasks), vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn clicking_another_column_replaces_the_keys() {
+        let mut sort = TaskSort::default();
+
+        sort.sort_by(SortColumn::Priority());
+        sort.then_by(SortColumn::DueDate());
+        sort.sort_by(SortColumn::Status());
+
+        assert_eq!(sort.keys, vec![SortKey { column : SortColumn::Status(), direction : SortDirection::Ascending() }]);
+        assert_eq!(sort.key(SortColumn::Status()).map(|(index, _)| index), Some(0));
+        assert_eq!(sort.key(SortColumn::Priority()), None);
+    }
+}
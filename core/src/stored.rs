@@ -0,0 +1,151 @@
+use serde::Deserialize;
+
+use crate::model::{Comment, Task, TaskId, TaskPriority, TaskStatus, TrashedTask, UserDirectory, UserId};
+use crate::tasks::number_comments;
+use crate::time::Timestamp;
+
+// Tasks as they are stored in localStorage, including the formats stored by earlier versions of the app.
+// Stored Tasks are read with these types and migrated to the model with into_task, which is given the directory of
+// Users, since earlier versions stored the users themselves instead of their ids.
+#[derive(Deserialize)]
+pub struct StoredTask {
+    id : TaskId,
+    name : String,
+    description : String,
+    assigned_to : Vec<StoredUser>,
+    due_date : Timestamp,
+    priority : TaskPriority,
+    status : TaskStatus,
+    comments : Vec<StoredComment>
+}
+
+// An assigned user, stored as an id, or before user ids were added, as an object with a name and image
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredUser {
+    Id(UserId),
+    User { name : String },
+}
+
+// A comment, stored without an id, or with the name of its author instead of their id and without a date, by earlier
+// versions of the app
+#[derive(Deserialize)]
+struct StoredComment {
+    #[serde(default)]
+    id : u32,
+    #[serde(default)]
+    user_id : Option<UserId>,
+    #[serde(default)]
+    user : Option<String>,
+    message : String,
+    #[serde(default)]
+    created_at : Option<Timestamp>,
+    #[serde(default)]
+    edited_at : Option<Timestamp>
+}
+
+#[derive(Deserialize)]
+pub struct StoredTrashedTask {
+    task : StoredTask,
+    deleted_at : Timestamp
+}
+
+impl StoredTask {
+    // Migrates the stored Task to the model. Users stored by name are matched to the directory by name; assigned users
+    // that are not in the directory are unassigned, and comment authors that are not in the directory are shown as unknown.
+    // Comments stored without ids are given the next ids of the Task, and comments stored without a date are dated
+    // with the due date of the Task, the only time stored with it.
+    pub fn into_task(self, users : &UserDirectory) -> Task {
+        let due_date = self.due_date;
+
+        let mut comments = self.comments.into_iter().map(|comment| Comment {
+            id : comment.id,
+            user_id : comment.user_id
+                .or_else(|| comment.user.and_then(|name| users.find_by_name(&name)))
+                .unwrap_or_default(),
+            message : comment.message,
+            created_at : comment.created_at.unwrap_or(due_date),
+            edited_at : comment.edited_at
+        }).collect::<Vec<_>>();
+
+        number_comments(&mut comments);
+
+        Task {
+            id : self.id,
+            name : self.name,
+            description : self.description,
+            assigned_to : self.assigned_to.into_iter().filter_map(|stored_user| match stored_user {
+                StoredUser::Id(user_id) => Some(user_id),
+                StoredUser::User { name } => users.find_by_name(&name),
+            }).collect(),
+            due_date : self.due_date,
+            priority : self.priority,
+            status : self.status,
+            comments
+        }
+    }
+}
+
+impl StoredTrashedTask {
+    pub fn into_trashed_task(self, users : &UserDirectory) -> TrashedTask {
+        TrashedTask { task : self.task.into_task(users), deleted_at : self.deleted_at }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::get_users;
+
+    #[test]
+    fn migrates_tasks_stored_with_users_instead_of_user_ids() {
+        let stored_task : StoredTask = serde_json::from_str(r#"{
+            "id": 1,
+            "name": "Task",
+            "description": "",
+            "assigned_to": [{ "name": "Fatima", "image": "person2.png" }, 4, { "name": "Nobody", "image": "" }],
+            "due_date": 1741132800000.0,
+            "priority": { "High": [] },
+            "status": { "New": [] },
+            "comments": [
+                { "user": "John", "message": "A comment stored before comments had ids", "image": "person3.png" },
+                { "id": 1, "user_id": 3, "message": "A comment", "created_at": 1741000000000.0 },
+                { "id": 0, "user": "Nobody", "message": "A comment by a user who is not in the directory", "image": "" }
+            ]
+        }"#).unwrap();
+
+        let task = stored_task.into_task(&UserDirectory::new(get_users()));
+
+        assert_eq!(task.assigned_to, vec![UserId(2), UserId(4)]);
+        assert_eq!(task.due_date, Timestamp(1_741_132_800_000.0));
+        assert_eq!(task.comments.iter().map(|comment| comment.id).collect::<Vec<_>>(), vec![2, 1, 3]);
+        assert_eq!(task.comments.iter().map(|comment| comment.user_id).collect::<Vec<_>>(), vec![UserId(3), UserId(3), UserId(0)]);
+        assert_eq!(task.comments[0].created_at, task.due_date);
+        assert_eq!(task.comments[1].created_at, Timestamp(1_741_000_000_000.0));
+    }
+
+    #[test]
+    fn reads_tasks_stored_by_the_current_version() {
+        let task = task_with_comment();
+        let json = serde_json::to_string(&task).unwrap();
+        let stored_task : StoredTask = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(stored_task.into_task(&UserDirectory::default()), task);
+    }
+
+    fn task_with_comment() -> Task {
+        Task {
+            id : TaskId(3),
+            name : "Task".to_string(),
+            assigned_to : vec![UserId(1), UserId(9)],
+            comments : vec![Comment {
+                id : 1,
+                user_id : UserId(2),
+                message : "A comment".to_string(),
+                created_at : Timestamp(1_741_132_800_000.0),
+                edited_at : Some(Timestamp(1_741_132_900_000.0))
+            }],
+            ..Task::default()
+        }
+    }
+}
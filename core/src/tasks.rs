@@ -0,0 +1,146 @@
+use crate::model::{Comment, Task, TaskId, TrashedTask, UserId};
+use crate::time::Timestamp;
+
+// Inserts the Task in the position given by its id, so that the list stays sorted by id
+pub fn insert_task(tasks : &mut Vec<Task>, task : Task) {
+    let position = tasks.partition_point(|t| t.id < task.id);
+    tasks.insert(position, task);
+}
+
+// Removes the Task with the given id from the list and returns it
+pub fn take_task(tasks : &mut Vec<Task>, task_id : TaskId) -> Option<Task> {
+    let index = tasks.iter().position(|task| task.id == task_id)?;
+    Some(tasks.remove(index))
+}
+
+// Unassigns the user if they are assigned, otherwise the user is assigned
+pub fn toggle_assigned_user(assigned_to : &mut Vec<UserId>, user_id : UserId) {
+    if let Some(index) = assigned_to.iter().position(|assigned_user_id| *assigned_user_id == user_id) {
+        assigned_to.remove(index);
+    } else {
+        assigned_to.push(user_id);
+    }
+}
+
+// The id given to the next comment posted on a Task
+pub fn next_comment_id(comments : &[Comment]) -> u32 {
+    comments.iter().map(|comment| comment.id).max().unwrap_or(0) + 1
+}
+
+// Gives the comments stored before comments had ids, which have the id 0, the next ids of their Task in the order
+// they are listed, so that each comment can be edited and deleted on its own
+pub fn number_comments(comments : &mut [Comment]) {
+    let next_ids = next_comment_id(comments)..;
+
+    for (id, comment) in next_ids.zip(comments.iter_mut().filter(|comment| comment.id == 0)) {
+        comment.id = id;
+    }
+}
+
+// Sorts the comments in the order they were posted
+pub fn sort_comments_by_posted(comments : &mut [Comment]) {
+    comments.sort_by(|a, b| a.created_at.total_cmp(&b.created_at));
+}
+
+// The highest Task id in the list and the trash, which new Task ids must be greater than
+pub fn max_task_id(tasks : &[Task], trash : &[TrashedTask]) -> TaskId {
+    tasks.iter().map(|task| task.id)
+        .chain(trash.iter().map(|trashed_task| trashed_task.task.id))
+        .max()
+        .unwrap_or_default()
+}
+
+// True if any trashed Task has been kept longer than the retention period
+pub fn has_expired_tasks(trash : &[TrashedTask], retention_days : u32, now : Timestamp) -> bool {
+    trash.iter().any(|trashed_task| trashed_task.purge_date(retention_days) < now)
+}
+
+// Removes the trashed Tasks that have been kept longer than the retention period
+pub fn purge_expired_tasks(trash : &mut Vec<TrashedTask>, retention_days : u32, now : Timestamp) {
+    trash.retain(|trashed_task| trashed_task.purge_date(retention_days) >= now);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id : u32) -> Task {
+        Task { id : TaskId(id), ..Task::default() }
+    }
+
+    fn comment(id : u32, created_at : f64) -> Comment {
+        Comment { id, user_id : UserId(1), message : String::new(), created_at : Timestamp(created_at), edited_at : None }
+    }
+
+    #[test]
+    fn inserts_and_takes_tasks_by_id() {
+        let mut tasks = vec![task(1), task(3), task(5)];
+
+        insert_task(&mut tasks, task(4));
+
+        assert_eq!(tasks.iter().map(|task| task.id.0).collect::<Vec<_>>(), vec![1, 3, 4, 5]);
+        assert_eq!(take_task(&mut tasks, TaskId(3)).map(|task| task.id), Some(TaskId(3)));
+        assert_eq!(take_task(&mut tasks, TaskId(3)), None);
+        assert_eq!(tasks.len(), 3);
+    }
+
+    #[test]
+    fn toggles_assigned_users() {
+        let mut assigned_to = vec![UserId(1), UserId(2)];
+
+        toggle_assigned_user(&mut assigned_to, UserId(1));
+        toggle_assigned_user(&mut assigned_to, UserId(3));
+
+        assert_eq!(assigned_to, vec![UserId(2), UserId(3)]);
+    }
+
+    #[test]
+    fn orders_comments_and_numbers_new_ones() {
+        let mut comments = vec![comment(2, 20.0), comment(3, 10.0), comment(1, 5.0)];
+
+        sort_comments_by_posted(&mut comments);
+
+        assert_eq!(comments.iter().map(|comment| comment.id).collect::<Vec<_>>(), vec![1, 3, 2]);
+        assert_eq!(next_comment_id(&comments), 4);
+        assert_eq!(next_comment_id(&[]), 1);
+    }
+
+    #[test]
+    fn numbers_comments_stored_without_ids() {
+        let mut comments = vec![comment(0, 1.0), comment(2, 2.0), comment(0, 3.0)];
+
+        number_comments(&mut comments);
+
+        assert_eq!(comments.iter().map(|comment| comment.id).collect::<Vec<_>>(), vec![3, 2, 4]);
+
+        // Deleting one of the comments stored without an id keeps the other
+        let deleted_comment_id = comments[0].id;
+        comments.retain(|comment| comment.id != deleted_comment_id);
+
+        assert_eq!(comments.iter().map(|comment| comment.id).collect::<Vec<_>>(), vec![2, 4]);
+    }
+
+    #[test]
+    fn finds_the_max_task_id_in_the_list_and_the_trash() {
+        let trash = vec![TrashedTask { task : task(9), deleted_at : Timestamp(0.0) }];
+
+        assert_eq!(max_task_id(&[task(2), task(7)], &trash), TaskId(9));
+        assert_eq!(max_task_id(&[], &[]), TaskId(0));
+    }
+
+    #[test]
+    fn purges_tasks_kept_longer_than_the_retention_period() {
+        let now = Timestamp(0.0).add_days(100);
+        let mut trash = vec![
+            TrashedTask { task : task(1), deleted_at : now.add_days(-31) },
+            TrashedTask { task : task(2), deleted_at : now.add_days(-29) },
+        ];
+
+        assert!(has_expired_tasks(&trash, 30, now));
+
+        purge_expired_tasks(&mut trash, 30, now);
+
+        assert_eq!(trash.iter().map(|trashed_task| trashed_task.task.id).collect::<Vec<_>>(), vec![TaskId(2)]);
+        assert!(!has_expired_tasks(&trash, 30, now));
+    }
+}
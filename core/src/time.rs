@@ -0,0 +1,361 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+pub const MS_IN_MINUTE: f64 = 60_000.0;
+pub const MS_IN_HOUR: f64 = 3_600_000.0;
+pub const MS_IN_DAY: f64 = 86_400_000.0;
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// A point in time in milliseconds since the Unix epoch, the same value as a JavaScript Date's time.
+// It is serialized as the number of milliseconds, so Tasks stored before the type was added are read unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(pub f64);
+
+impl Timestamp {
+    pub fn add_days(self, days : i32) -> Self {
+        Self(self.0 + days as f64 * MS_IN_DAY)
+    }
+
+    pub fn add_hours(self, hours : i32) -> Self {
+        Self(self.0 + hours as f64 * MS_IN_HOUR)
+    }
+
+    // A total order, so that timestamps can be used to sort
+    pub fn total_cmp(&self, other : &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+
+    // The date and time of the timestamp in the time zone
+    pub fn to_local(self, zone : &impl TimeZone) -> LocalDateTime {
+
+        let local_ms = self.0 + zone.utc_offset_minutes(self) as f64 * MS_IN_MINUTE;
+
+        let days = (local_ms / MS_IN_DAY).floor();
+        let ms_of_day = local_ms - days * MS_IN_DAY;
+
+        let (year, month, day) = civil_from_days(days as i64);
+
+        LocalDateTime {
+            year,
+            month,
+            day,
+            hour : (ms_of_day / MS_IN_HOUR) as u32,
+            minute : ((ms_of_day % MS_IN_HOUR) / MS_IN_MINUTE) as u32
+        }
+    }
+
+    // The timestamp of the date and time in the time zone. Returns None if the date or time does not exist.
+    pub fn from_local(local : LocalDateTime, zone : &impl TimeZone) -> Option<Self> {
+
+        if !(1..=12).contains(&local.month) || local.day < 1 || local.day > days_in_month(local.year, local.month) || local.hour > 23 || local.minute > 59 {
+            return None;
+        }
+
+        let local_ms = days_from_civil(local.year, local.month, local.day) as f64 * MS_IN_DAY
+            + local.hour as f64 * MS_IN_HOUR
+            + local.minute as f64 * MS_IN_MINUTE;
+
+        // The offset depends on the timestamp being found. It is looked up at the local time read as UTC, and again at the
+        // timestamp that offset gives, which is right unless the local time is skipped or repeated by a daylight saving change.
+        let offset_guess = zone.utc_offset_minutes(Timestamp(local_ms));
+        let offset = zone.utc_offset_minutes(Timestamp(local_ms - offset_guess as f64 * MS_IN_MINUTE));
+
+        Some(Timestamp(local_ms - offset as f64 * MS_IN_MINUTE))
+    }
+
+    // The timestamp of midnight on the day of the timestamp, in the time zone
+    pub fn start_of_day(self, zone : &impl TimeZone) -> Self {
+        let local = self.to_local(zone);
+        Self::from_local(LocalDateTime { hour : 0, minute : 0, ..local }, zone).unwrap_or(self)
+    }
+
+    // The timestamp of midnight on the day that is the number of days after the day of the timestamp, in the time zone.
+    // Unlike add_days, the result is midnight even if a daylight saving change falls between the days.
+    pub fn start_of_day_after(self, days : i32, zone : &impl TimeZone) -> Self {
+        let local = self.to_local(zone);
+        let (year, month, day) = civil_from_days(days_from_civil(local.year, local.month, local.day) + days as i64);
+        Self::from_local(LocalDateTime { year, month, day, hour : 0, minute : 0 }, zone).unwrap_or(self)
+    }
+
+    // The timestamp of midnight on the Monday of the week of the timestamp, in the time zone
+    pub fn start_of_week(self, zone : &impl TimeZone) -> Self {
+        let local = self.to_local(zone);
+
+        // 1970-01-01 was a Thursday, three days after a Monday
+        let days_since_monday = (days_from_civil(local.year, local.month, local.day) + 3).rem_euclid(7);
+
+        self.start_of_day_after(-(days_since_monday as i32), zone)
+    }
+}
+
+// The offset from UTC of a time zone, which can change during the year for daylight saving time
+pub trait TimeZone {
+    // The number of minutes the time zone is ahead of UTC at the timestamp
+    fn utc_offset_minutes(&self, timestamp : Timestamp) -> i32;
+}
+
+// A time zone that is the same number of minutes ahead of UTC all year
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FixedOffset(pub i32);
+
+pub const UTC: FixedOffset = FixedOffset(0);
+
+impl TimeZone for FixedOffset {
+    fn utc_offset_minutes(&self, _timestamp : Timestamp) -> i32 {
+        self.0
+    }
+}
+
+// A date and time in a time zone. Months and days start at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalDateTime {
+    pub year : i32,
+    pub month : u32,
+    pub day : u32,
+    pub hour : u32,
+    pub minute : u32
+}
+
+// Formats the date of the timestamp, for example 5 Mar 2025
+pub fn format_date(timestamp : Timestamp, zone : &impl TimeZone) -> String {
+
+    let local = timestamp.to_local(zone);
+
+    format!("{} {} {}", local.day, MONTHS[local.month as usize - 1], local.year)
+}
+
+// Formats the date and time of the timestamp, for example 5 Mar 2025 14:05
+pub fn format_date_time(timestamp : Timestamp, zone : &impl TimeZone) -> String {
+
+    let local = timestamp.to_local(zone);
+
+    format!("{} {:02}:{:02}", format_date(timestamp, zone), local.hour, local.minute)
+}
+
+// Formats how long ago the timestamp was from now, for example "2 hours ago".
+// Timestamps older than a week are formatted as a date.
+pub fn format_relative_time(timestamp : Timestamp, now : Timestamp, zone : &impl TimeZone) -> String {
+
+    let elapsed = (now.0 - timestamp.0).max(0.0);
+
+    let plural = |count : f64, unit : &str| {
+        let count = count as u32;
+        if count == 1 { format!("1 {} ago", unit) } else { format!("{} {}s ago", count, unit) }
+    };
+
+    if elapsed < MS_IN_MINUTE {
+        "just now".to_string()
+    } else if elapsed < MS_IN_HOUR {
+        plural(elapsed / MS_IN_MINUTE, "minute")
+    } else if elapsed < MS_IN_DAY {
+        plural(elapsed / MS_IN_HOUR, "hour")
+    } else if elapsed < 7.0 * MS_IN_DAY {
+        plural(elapsed / MS_IN_DAY, "day")
+    } else {
+        format_date(timestamp, zone)
+    }
+}
+
+// Returns the date of the timestamp, in the YYYY-MM-DD format used by date inputs
+pub fn to_date_input_value(timestamp : Timestamp, zone : &impl TimeZone) -> String {
+
+    let local = timestamp.to_local(zone);
+
+    format!("{:04}-{:02}-{:02}", local.year, local.month, local.day)
+}
+
+// Returns the time of the timestamp, in the HH:MM format used by time inputs.
+// A timestamp at midnight has no time picked, so an empty string is returned.
+pub fn to_time_input_value(timestamp : Timestamp, zone : &impl TimeZone) -> String {
+
+    let local = timestamp.to_local(zone);
+
+    if local.hour == 0 && local.minute == 0 {
+        return String::new();
+    }
+
+    format!("{:02}:{:02}", local.hour, local.minute)
+}
+
+// Combines the values of a date input and a time input into a timestamp. An empty time is midnight.
+// Returns None if the values are not in the YYYY-MM-DD and HH:MM formats.
+pub fn from_date_time_input_values(date : &str, time : &str, zone : &impl TimeZone) -> Option<Timestamp> {
+
+    let mut date_parts = date.splitn(3, '-');
+
+    let (Some(year), Some(month), Some(day)) = (date_parts.next(), date_parts.next(), date_parts.next()) else {
+        return None;
+    };
+
+    let (hour, minute) = match time.split_once(':') {
+        Some((hour, minute)) => (hour.parse().ok()?, minute.parse().ok()?),
+        None if time.is_empty() => (0, 0),
+        None => return None,
+    };
+
+    Timestamp::from_local(LocalDateTime { year: year.parse().ok()?, month: month.parse().ok()?, day: day.parse().ok()?, hour, minute }, zone)
+}
+
+fn is_leap_year(year : i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year : i32, month : u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+// The number of days from 1970-01-01 to the date, from Howard Hinnant's days_from_civil algorithm
+fn days_from_civil(year : i32, month : u32, day : u32) -> i64 {
+
+    let year = if month <= 2 { year as i64 - 1 } else { year as i64 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The date that is the number of days from 1970-01-01, from Howard Hinnant's civil_from_days algorithm
+fn civil_from_days(days : i64) -> (i32, u32, u32) {
+
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year as i32, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A time zone that is one hour ahead of UTC, and two hours ahead from April to October
+    struct DaylightSavingZone;
+
+    impl TimeZone for DaylightSavingZone {
+        fn utc_offset_minutes(&self, timestamp : Timestamp) -> i32 {
+            let month = timestamp.to_local(&UTC).month;
+            if (4..=10).contains(&month) { 120 } else { 60 }
+        }
+    }
+
+    fn local(year : i32, month : u32, day : u32, hour : u32, minute : u32) -> LocalDateTime {
+        LocalDateTime { year, month, day, hour, minute }
+    }
+
+    #[test]
+    fn converts_dates_to_and_from_days_since_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+
+        for days in -1000..1000 {
+            let (year, month, day) = civil_from_days(days * 37);
+            assert_eq!(days_from_civil(year, month, day), days * 37);
+        }
+    }
+
+    #[test]
+    fn local_date_time_round_trips_through_timestamp() {
+        let zone = FixedOffset(-300);
+        let date_time = local(2025, 3, 5, 14, 5);
+
+        let timestamp = Timestamp::from_local(date_time, &zone).unwrap();
+
+        assert_eq!(timestamp.to_local(&zone), date_time);
+        assert_eq!(timestamp.to_local(&UTC), local(2025, 3, 5, 19, 5));
+    }
+
+    #[test]
+    fn from_local_uses_the_offset_at_the_date() {
+        let winter = Timestamp::from_local(local(2025, 1, 10, 0, 0), &DaylightSavingZone).unwrap();
+        let summer = Timestamp::from_local(local(2025, 7, 10, 0, 0), &DaylightSavingZone).unwrap();
+
+        assert_eq!(winter.to_local(&UTC), local(2025, 1, 9, 23, 0));
+        assert_eq!(summer.to_local(&UTC), local(2025, 7, 9, 22, 0));
+        assert_eq!(to_date_input_value(summer, &DaylightSavingZone), "2025-07-10");
+    }
+
+    #[test]
+    fn from_local_rejects_dates_that_do_not_exist() {
+        assert!(Timestamp::from_local(local(2025, 2, 29, 0, 0), &UTC).is_none());
+        assert!(Timestamp::from_local(local(2024, 2, 29, 0, 0), &UTC).is_some());
+        assert!(Timestamp::from_local(local(2025, 13, 1, 0, 0), &UTC).is_none());
+        assert!(Timestamp::from_local(local(2025, 1, 1, 24, 0), &UTC).is_none());
+    }
+
+    #[test]
+    fn start_of_day_is_local_midnight() {
+        let zone = FixedOffset(60);
+        let timestamp = Timestamp::from_local(local(2025, 3, 5, 0, 30), &zone).unwrap();
+
+        assert_eq!(timestamp.start_of_day(&zone).to_local(&zone), local(2025, 3, 5, 0, 0));
+    }
+
+    #[test]
+    fn reads_and_writes_input_values() {
+        let timestamp = from_date_time_input_values("2025-03-05", "09:30", &UTC).unwrap();
+
+        assert_eq!(to_date_input_value(timestamp, &UTC), "2025-03-05");
+        assert_eq!(to_time_input_value(timestamp, &UTC), "09:30");
+
+        let midnight = from_date_time_input_values("2025-03-05", "", &UTC).unwrap();
+
+        assert_eq!(to_time_input_value(midnight, &UTC), "");
+        assert!(from_date_time_input_values("2025-03", "", &UTC).is_none());
+        assert!(from_date_time_input_values("2025-03-05", "9", &UTC).is_none());
+    }
+
+    #[test]
+    fn finds_the_start_of_later_days_and_of_the_week() {
+        // Wednesday 5 March 2025
+        let timestamp = Timestamp::from_local(local(2025, 3, 5, 14, 5), &DaylightSavingZone).unwrap();
+
+        assert_eq!(timestamp.start_of_day_after(1, &DaylightSavingZone).to_local(&DaylightSavingZone), local(2025, 3, 6, 0, 0));
+        assert_eq!(timestamp.start_of_week(&DaylightSavingZone).to_local(&DaylightSavingZone), local(2025, 3, 3, 0, 0));
+
+        // Midnight is kept across the change to daylight saving time at the start of April
+        let march = Timestamp::from_local(local(2025, 3, 31, 0, 0), &DaylightSavingZone).unwrap();
+        assert_eq!(march.start_of_day_after(2, &DaylightSavingZone).to_local(&DaylightSavingZone), local(2025, 4, 2, 0, 0));
+
+        // A Monday is the start of its own week, and a Sunday belongs to the week before
+        let monday = Timestamp::from_local(local(2025, 3, 3, 9, 0), &UTC).unwrap();
+        assert_eq!(monday.start_of_week(&UTC).to_local(&UTC), local(2025, 3, 3, 0, 0));
+        assert_eq!(monday.add_days(-1).start_of_week(&UTC).to_local(&UTC), local(2025, 2, 24, 0, 0));
+    }
+
+    #[test]
+    fn formats_dates() {
+        let timestamp = from_date_time_input_values("2025-03-05", "14:05", &UTC).unwrap();
+
+        assert_eq!(format_date(timestamp, &UTC), "5 Mar 2025");
+        assert_eq!(format_date_time(timestamp, &UTC), "5 Mar 2025 14:05");
+    }
+
+    #[test]
+    fn formats_relative_times() {
+        let now = from_date_time_input_values("2025-03-20", "12:00", &UTC).unwrap();
+
+        assert_eq!(format_relative_time(Timestamp(now.0 - 30_000.0), now, &UTC), "just now");
+        assert_eq!(format_relative_time(Timestamp(now.0 - MS_IN_MINUTE), now, &UTC), "1 minute ago");
+        assert_eq!(format_relative_time(now.add_hours(-3), now, &UTC), "3 hours ago");
+        assert_eq!(format_relative_time(now.add_days(-2), now, &UTC), "2 days ago");
+        assert_eq!(format_relative_time(now.add_days(-10), now, &UTC), "10 Mar 2025");
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::model::Task;
+use crate::time::Timestamp;
+
+// The inputs of the TaskForm that a validation error can be shown next to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskField {
+    Name,
+    Description,
+    DueDate,
+    AssignedTo,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskError {
+    NameRequired,
+    NameTooLong { max_length : usize },
+    DescriptionTooLong { max_length : usize },
+    DueDateInPast,
+    NoAssignees,
+}
+
+impl TaskError {
+    pub fn field(&self) -> TaskField {
+        match self {
+            Self::NameRequired | Self::NameTooLong { .. } => TaskField::Name,
+            Self::DescriptionTooLong { .. } => TaskField::Description,
+            Self::DueDateInPast => TaskField::DueDate,
+            Self::NoAssignees => TaskField::AssignedTo,
+        }
+    }
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NameRequired => write!(f, "A task name is required"),
+            Self::NameTooLong { max_length } => write!(f, "The task name can not be longer than {} characters", max_length),
+            Self::DescriptionTooLong { max_length } => write!(f, "The description can not be longer than {} characters", max_length),
+            Self::DueDateInPast => write!(f, "The due date is in the past"),
+            Self::NoAssignees => write!(f, "At least one user must be assigned"),
+        }
+    }
+}
+
+// The rules a Task is validated against
+#[derive(Clone)]
+pub struct ValidationRules {
+    pub max_name_length : usize,
+    pub max_description_length : usize,
+    // Set when the user has confirmed that the Task can be due before today
+    pub allow_past_due_date : bool,
+    pub require_assignee : bool,
+}
+
+impl Default for ValidationRules {
+    fn default() -> Self {
+        Self {
+            max_name_length : 100,
+            max_description_length : 2000,
+            allow_past_due_date : false,
+            require_assignee : true,
+        }
+    }
+}
+
+// Validates the Task against the rules and returns every error found.
+// start_of_today is the timestamp of midnight today, a Task due any time today is not in the past.
+pub fn validate_task(task : &Task, rules : &ValidationRules, start_of_today : Timestamp) -> Result<(), Vec<TaskError>> {
+
+    let mut errors = Vec::new();
+
+    let name_length = task.name.trim().chars().count();
+
+    if name_length == 0 {
+        errors.push(TaskError::NameRequired);
+    } else if name_length > rules.max_name_length {
+        errors.push(TaskError::NameTooLong { max_length : rules.max_name_length });
+    }
+
+    if task.description.chars().count() > rules.max_description_length {
+        errors.push(TaskError::DescriptionTooLong { max_length : rules.max_description_length });
+    }
+
+    if !rules.allow_past_due_date && task.due_date < start_of_today {
+        errors.push(TaskError::DueDateInPast);
+    }
+
+    if rules.require_assignee && task.assigned_to.is_empty() {
+        errors.push(TaskError::NoAssignees);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::UserId;
+    use crate::time::MS_IN_HOUR;
+
+    const START_OF_TODAY: Timestamp = Timestamp(1_741_132_800_000.0);
+
+    fn valid_task() -> Task {
+        Task {
+            name : "Write release notes".to_string(),
+            assigned_to : vec![UserId(1)],
+            due_date : START_OF_TODAY.add_days(1),
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn accepts_a_valid_task() {
+        assert_eq!(validate_task(&valid_task(), &ValidationRules::default(), START_OF_TODAY), Ok(()));
+    }
+
+    #[test]
+    fn returns_every_error() {
+        let task = Task { name : "  ".to_string(), assigned_to : vec![], due_date : START_OF_TODAY.add_days(-1), ..valid_task() };
+
+        assert_eq!(
+            validate_task(&task, &ValidationRules::default(), START_OF_TODAY),
+            Err(vec![TaskError::NameRequired, TaskError::DueDateInPast, TaskError::NoAssignees])
+        );
+    }
+
+    #[test]
+    fn limits_the_length_of_the_name_and_description() {
+        let rules = ValidationRules { max_name_length : 5, max_description_length : 10, ..ValidationRules::default() };
+        let task = Task { name : "Too long".to_string(), description : "A description".to_string(), ..valid_task() };
+
+        assert_eq!(
+            validate_task(&task, &rules, START_OF_TODAY),
+            Err(vec![TaskError::NameTooLong { max_length : 5 }, TaskError::DescriptionTooLong { max_length : 10 }])
+        );
+    }
+
+    #[test]
+    fn a_task_due_earlier_today_is_not_in_the_past() {
+        let task = Task { due_date : Timestamp(START_OF_TODAY.0 + MS_IN_HOUR), ..valid_task() };
+
+        assert_eq!(validate_task(&task, &ValidationRules::default(), START_OF_TODAY), Ok(()));
+    }
+
+    #[test]
+    fn a_past_due_date_can_be_allowed() {
+        let rules = ValidationRules { allow_past_due_date : true, ..ValidationRules::default() };
+        let task = Task { due_date : START_OF_TODAY.add_days(-3), ..valid_task() };
+
+        assert_eq!(validate_task(&task, &rules, START_OF_TODAY), Ok(()));
+    }
+}
@@ -0,0 +1,244 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::filter::{DueRange, TaskFilter};
+use crate::model::{TaskPriority, TaskStatus, UserId};
+use crate::sort::{SortColumn, SortDirection, SortKey, TaskSort};
+use crate::time::{from_date_time_input_values, to_date_input_value, TimeZone};
+
+// The names of the URL query parameters a TaskView is kept in
+const STATUS_PARAM: &str = "status";
+const PRIORITY_PARAM: &str = "priority";
+const ASSIGNEE_PARAM: &str = "assignee";
+const DUE_PARAM: &str = "due";
+const DUE_FROM_PARAM: &str = "due_from";
+const DUE_TO_PARAM: &str = "due_to";
+const SORT_PARAM: &str = "sort";
+const SEARCH_PARAM: &str = "q";
+
+// The filter, sort and search the TaskList is shown with
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskView {
+    pub filter : TaskFilter,
+    pub sort : TaskSort,
+    pub search : String
+}
+
+impl TaskView {
+    // Returns the view as URL query parameters. Only the parts of the view that are set are included, e.g.
+    // status=new,pending&due=week&sort=-priority,due&q=login. A sort key starting with a minus sign is descending,
+    // and the dates of a custom due range are in the YYYY-MM-DD format in the time zone.
+    pub fn query_params(&self, zone : &impl TimeZone) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        let mut push = |name : &str, values : Vec<String>| {
+            if !values.is_empty() {
+                params.push((name.to_string(), values.join(",")));
+            }
+        };
+
+        push(STATUS_PARAM, self.filter.statuses.iter().map(|status| status.key().to_string()).collect());
+        push(PRIORITY_PARAM, self.filter.priorities.iter().map(|priority| priority.key().to_string()).collect());
+        push(ASSIGNEE_PARAM, self.filter.assignees.iter().map(|user_id| user_id.0.to_string()).collect());
+
+        if let Some(due) = self.filter.due {
+            push(DUE_PARAM, vec![due.key().to_string()]);
+
+            if let DueRange::Custom { from, to } = due {
+                push(DUE_FROM_PARAM, from.map(|from| to_date_input_value(from, zone)).into_iter().collect());
+                push(DUE_TO_PARAM, to.map(|to| to_date_input_value(to, zone)).into_iter().collect());
+            }
+        }
+
+        push(SORT_PARAM, self.sort.keys.iter().map(|key| match key.direction {
+            SortDirection::Ascending() => key.column.key().to_string(),
+            SortDirection::Descending() => format!("-{}", key.column.key()),
+        }).collect());
+
+        if !self.search.is_empty() {
+            params.push((SEARCH_PARAM.to_string(), self.search.clone()));
+        }
+
+        params
+    }
+
+    // Reads the view from URL query parameters. Parameters and values that are not recognised are ignored,
+    // so a link with a status or column that no longer exists still opens the rest of the view.
+    pub fn from_query_params(params : &[(String, String)], zone : &impl TimeZone) -> Self {
+        let param = |name : &str| params.iter().find(|(param, _)| param == name).map(|(_, value)| value.as_str());
+        let values = |name : &str| param(name).map(|value| value.split(',').collect::<Vec<_>>()).unwrap_or_default();
+        let date = |name : &str| param(name).and_then(|date| from_date_time_input_values(date, "", zone));
+
+        let due = match param(DUE_PARAM) {
+            Some("custom") => Some(DueRange::Custom { from : date(DUE_FROM_PARAM), to : date(DUE_TO_PARAM) }),
+            Some(key) => DueRange::PRESETS.into_iter().find(|due| due.key() == key),
+            None => None
+        };
+
+        Self {
+            filter : TaskFilter {
+                statuses : values(STATUS_PARAM).into_iter().filter_map(|key| TaskStatus::from_str(key).ok()).collect(),
+                priorities : values(PRIORITY_PARAM).into_iter().filter_map(|key| TaskPriority::from_str(key).ok()).collect(),
+                assignees : values(ASSIGNEE_PARAM).into_iter().filter_map(|id| id.parse().ok().map(UserId)).collect(),
+                due
+            },
+            sort : TaskSort {
+                keys : values(SORT_PARAM).into_iter().filter_map(|key| match key.strip_prefix('-') {
+                    Some(key) => SortColumn::from_str(key).ok().map(|column| SortKey { column, direction : SortDirection::Descending() }),
+                    None => SortColumn::from_str(key).ok().map(|column| SortKey { column, direction : SortDirection::Ascending() }),
+                }).collect()
+            },
+            search : param(SEARCH_PARAM).unwrap_or_default().to_string()
+        }
+    }
+
+    // Whether the query parameters hold a view. A link without them opens the app with the view the user last had.
+    pub fn is_in_query_params(params : &[(String, String)]) -> bool {
+        params.iter().any(|(name, _)| {
+            [STATUS_PARAM, PRIORITY_PARAM, ASSIGNEE_PARAM, DUE_PARAM, SORT_PARAM, SEARCH_PARAM].contains(&name.as_str())
+        })
+    }
+}
+
+// A view saved with a name, listed in the sidebar so that it can be opened again with one click
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name : String,
+    pub view : TaskView
+}
+
+// Saves the view under the name. A view that is saved with the name of another view replaces it.
+pub fn save_view(saved_views : &mut Vec<SavedView>, name : &str, view : TaskView) {
+    let name = name.trim().to_string();
+
+    match saved_views.iter_mut().find(|saved_view| saved_view.name == name) {
+        Some(saved_view) => saved_view.view = view,
+        None => saved_views.push(SavedView { name, view })
+    }
+}
+
+// Parses a URL query string, with or without the leading question mark, into decoded name and value pairs
+pub fn parse_query(query : &str) -> Vec<(String, String)> {
+    query.trim_start_matches('?')
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (decode(name), decode(value)),
+            None => (decode(pair), String::new())
+        })
+        .collect()
+}
+
+// Formats name and value pairs as a URL query string with a leading question mark, or an empty string without pairs.
+// Commas are kept, since they separate the values of a parameter.
+pub fn format_query(params : &[(String, String)]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+
+    let pairs = params.iter().map(|(name, value)| format!("{}={}", encode(name), encode(value))).collect::<Vec<_>>();
+
+    format!("?{}", pairs.join("&"))
+}
+
+fn encode(text : &str) -> String {
+    text.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte)
+    }).collect()
+}
+
+// Decodes percent-encoded bytes, and plus signs as spaces. Invalid escapes are kept as they are.
+fn decode(text : &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = bytes.get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[index], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            (b'+', None) => {
+                decoded.push(b' ');
+                index += 1;
+            },
+            (byte, None) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{LocalDateTime, Timestamp, UTC};
+
+    fn view() -> TaskView {
+        TaskView {
+            filter : TaskFilter {
+                statuses : vec![TaskStatus::New(), TaskStatus::InProgress()],
+                priorities : vec![TaskPriority::High()],
+                assignees : vec![UserId(2)],
+                due : Some(DueRange::Custom {
+                    from : Timestamp::from_local(LocalDateTime { year : 2025, month : 3, day : 3, hour : 0, minute : 0 }, &UTC),
+                    to : None
+                })
+            },
+            sort : TaskSort { keys : vec![
+                SortKey { column : SortColumn::Priority(), direction : SortDirection::Descending() },
+                SortKey { column : SortColumn::DueDate(), direction : SortDirection::Ascending() }
+            ]},
+            search : "login & signup, 100%".to_string()
+        }
+    }
+
+    #[test]
+    fn keeps_the_view_in_the_query_string() {
+        let query = format_query(&view().query_params(&UTC));
+
+        assert_eq!(query, "?status=new,inprogress&priority=high&assignee=2&due=custom&due_from=2025-03-03&sort=-priority,due&q=login%20%26%20signup,%20100%25");
+        assert_eq!(TaskView::from_query_params(&parse_query(&query), &UTC), view());
+    }
+
+    #[test]
+    fn an_empty_view_has_no_query_string() {
+        let params = TaskView::default().query_params(&UTC);
+
+        assert_eq!(format_query(&params), "");
+        assert!(!TaskView::is_in_query_params(&parse_query("?task=3")));
+        assert!(TaskView::is_in_query_params(&parse_query("?task=3&q=login")));
+    }
+
+    #[test]
+    fn ignores_values_that_are_not_recognised() {
+        let view = TaskView::from_query_params(&parse_query("status=new,done&sort=-size,name&due=someday&q=a+b&x"), &UTC);
+
+        assert_eq!(view.filter.statuses, vec![TaskStatus::New()]);
+        assert_eq!(view.filter.due, None);
+        assert_eq!(view.sort.keys, vec![SortKey { column : SortColumn::Name(), direction : SortDirection::Ascending() }]);
+        assert_eq!(view.search, "a b");
+    }
+
+    #[test]
+    fn saving_a_view_with_the_same_name_replaces_it() {
+        let mut saved_views = Vec::new();
+
+        save_view(&mut saved_views, "Mine", TaskView::default());
+        save_view(&mut saved_views, "High priority", TaskView::default());
+        save_view(&mut saved_views, " Mine ", view());
+
+        assert_eq!(saved_views.iter().map(|saved_view| saved_view.name.as_str()).collect::<Vec<_>>(), vec!["Mine", "High priority"]);
+        assert_eq!(saved_views[0].view, view());
+    }
+}
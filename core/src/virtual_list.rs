@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+// The math of a virtualized list, where only the rows in the viewport are rendered. Every row has the same height, and
+// positions are in pixels from the top of the list.
+
+// The rows that are in the viewport, with buffer_rows extra rows above and below it so that scrolling does not show gaps
+// before the rows are rendered
+pub fn visible_range(row_count : usize, row_height : f64, scroll_top : f64, viewport_height : f64, buffer_rows : usize) -> Range<usize> {
+
+    if row_count == 0 || row_height <= 0.0 {
+        return 0..0;
+    }
+
+    let first_row = (scroll_top.max(0.0) / row_height).floor() as usize;
+    let last_row = ((scroll_top.max(0.0) + viewport_height.max(0.0)) / row_height).ceil() as usize;
+
+    let start = first_row.saturating_sub(buffer_rows).min(row_count);
+    let end = (last_row + buffer_rows).min(row_count);
+
+    start..end
+}
+
+// The scroll position that brings the row fully into the viewport, or None if the row is already fully visible
+pub fn scroll_top_to_show(index : usize, row_height : f64, scroll_top : f64, viewport_height : f64) -> Option<f64> {
+
+    let row_top = index as f64 * row_height;
+    let row_bottom = row_top + row_height;
+
+    if row_top < scroll_top {
+        Some(row_top)
+    } else if row_bottom > scroll_top + viewport_height {
+        Some((row_bottom - viewport_height).max(0.0))
+    } else {
+        None
+    }
+}
+
+// The scroll position that keeps the rows the user is looking at in place when the list changes, e.g. when it is filtered
+// or a row is added above the viewport. The first row at the top of the viewport that is still in the list is kept at the
+// same offset. If none of the rows from the top of the viewport down are left, the scroll position is unchanged.
+pub fn anchored_scroll_top<T : Eq + Hash>(previous_rows : &[T], rows : &[T], row_height : f64, scroll_top : f64) -> f64 {
+
+    if row_height <= 0.0 {
+        return scroll_top;
+    }
+
+    let top_row = (scroll_top.max(0.0) / row_height).floor() as usize;
+
+    let positions = rows.iter().enumerate().map(|(index, row)| (row, index)).collect::<HashMap<_, _>>();
+
+    previous_rows.iter().enumerate().skip(top_row)
+        .find_map(|(previous_index, row)| positions.get(row).map(|index| (previous_index, *index)))
+        .map(|(previous_index, index)| scroll_top + (index as f64 - previous_index as f64) * row_height)
+        .unwrap_or(scroll_top)
+        .max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_rows_in_the_viewport_and_the_buffer() {
+        assert_eq!(visible_range(1000, 50.0, 0.0, 500.0, 5), 0..15);
+        assert_eq!(visible_range(1000, 50.0, 1025.0, 500.0, 5), 15..36);
+        assert_eq!(visible_range(1000, 50.0, 49_800.0, 500.0, 5), 991..1000);
+        assert_eq!(visible_range(3, 50.0, 0.0, 500.0, 5), 0..3);
+        assert_eq!(visible_range(0, 50.0, 0.0, 500.0, 5), 0..0);
+    }
+
+    #[test]
+    fn scrolls_only_when_the_row_is_not_fully_visible() {
+        assert_eq!(scroll_top_to_show(4, 50.0, 100.0, 300.0), None);
+        assert_eq!(scroll_top_to_show(1, 50.0, 100.0, 300.0), Some(50.0));
+        assert_eq!(scroll_top_to_show(8, 50.0, 100.0, 300.0), Some(150.0));
+    }
+
+    #[test]
+    fn keeps_the_top_row_in_place_when_the_list_changes() {
+        let previous_rows = (0..100).collect::<Vec<u32>>();
+
+        // A row added above the viewport moves the rows down by one row
+        let mut rows = previous_rows.clone();
+        rows.insert(0, 1000);
+        assert_eq!(anchored_scroll_top(&previous_rows, &rows, 50.0, 1010.0), 1060.0);
+
+        // A row added below the viewport does not move it
+        rows = previous_rows.clone();
+        rows.push(1000);
+        assert_eq!(anchored_scroll_top(&previous_rows, &rows, 50.0, 1010.0), 1010.0);
+
+        // Filtering out the top row keeps the next row that is still listed in place
+        rows = previous_rows.iter().copied().filter(|row| row % 2 == 1).collect();
+        assert_eq!(anchored_scroll_top(&previous_rows, &rows, 50.0, 1010.0), 460.0);
+
+        // If no rows from the top of the viewport down are left, the position is unchanged
+        assert_eq!(anchored_scroll_top(&previous_rows, &[1, 2], 50.0, 1010.0), 1010.0);
+    }
+}
@@ -0,0 +1,467 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+
+use todo_core::model::{Comment, Task, TaskId, User, UserId};
+use todo_core::seed;
+use todo_core::time::Timestamp;
+
+// The version of the schema, stored in the database's user_version. A database with version 0 has just been created.
+const SCHEMA_VERSION: i32 = 2;
+
+const SCHEMA: &str = "
+    CREATE TABLE users (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        image TEXT NOT NULL
+    );
+
+    -- AUTOINCREMENT keeps the ids of deleted Tasks from being given to new Tasks, so a link to a Task never opens another Task
+    CREATE TABLE tasks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        due_date REAL NOT NULL,
+        priority TEXT NOT NULL,
+        status TEXT NOT NULL
+    );
+
+    -- The position keeps the assigned users in the order they were assigned
+    CREATE TABLE task_assignees (
+        task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        user_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (task_id, user_id)
+    );
+
+    -- Comment ids are numbered per Task
+    CREATE TABLE comments (
+        task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        message TEXT NOT NULL,
+        created_at REAL NOT NULL,
+        edited_at REAL,
+        PRIMARY KEY (task_id, id)
+    );
+";
+
+// Migrates a database with version 1, which gave the ids of deleted Tasks to new Tasks, to the AUTOINCREMENT tasks table.
+// The table is copied, since SQLite can not add AUTOINCREMENT to an existing table.
+const MIGRATE_TO_VERSION_2: &str = "
+    CREATE TABLE tasks_autoincrement (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        due_date REAL NOT NULL,
+        priority TEXT NOT NULL,
+        status TEXT NOT NULL
+    );
+
+    INSERT INTO tasks_autoincrement (id, name, description, due_date, priority, status)
+        SELECT id, name, description, due_date, priority, status FROM tasks;
+
+    DROP TABLE tasks;
+
+    ALTER TABLE tasks_autoincrement RENAME TO tasks;
+";
+
+// Opens the database, creating the tables and seeding them if the database is new
+pub fn open(path : impl AsRef<Path>, now : Timestamp) -> rusqlite::Result<Connection> {
+
+    let mut connection = Connection::open(path)?;
+
+    init(&mut connection, now)?;
+
+    Ok(connection)
+}
+
+// Creates the tables and seeds them if the database is new, migrates the tables if they have an earlier version,
+// and turns on the foreign keys of the connection
+fn init(connection : &mut Connection, now : Timestamp) -> rusqlite::Result<()> {
+
+    let version : i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    // The foreign keys are turned off during the migration, so that dropping the old tasks table does not delete the
+    // assignees and comments that reference it. They can only be turned off outside a transaction.
+    if version == 1 {
+        connection.pragma_update(None, "foreign_keys", false)?;
+
+        let transaction = connection.transaction()?;
+
+        transaction.execute_batch(MIGRATE_TO_VERSION_2)?;
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        transaction.commit()?;
+    }
+
+    connection.pragma_update(None, "foreign_keys", true)?;
+
+    if version == 0 {
+        let transaction = connection.transaction()?;
+
+        transaction.execute_batch(SCHEMA)?;
+
+        for user in seed::get_users() {
+            transaction.execute("INSERT INTO users (id, name, image) VALUES (?1, ?2, ?3)", params![user.id.0, user.name, user.image])?;
+        }
+
+        for mut task in seed::get_tasks(now) {
+            insert_task(&transaction, &mut task)?;
+        }
+
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+pub fn list_users(connection : &Connection) -> rusqlite::Result<Vec<User>> {
+    let mut statement = connection.prepare("SELECT id, name, image FROM users ORDER BY id")?;
+
+    let users = statement.query_map([], |row| Ok(User { id: UserId(row.get(0)?), name: row.get(1)?, image: row.get(2)? }))?;
+
+    users.collect()
+}
+
+pub fn list_tasks(connection : &Connection) -> rusqlite::Result<Vec<Task>> {
+    let mut statement = connection.prepare("SELECT id FROM tasks ORDER BY id")?;
+
+    let task_ids = statement.query_map([], |row| row.get::<_, u32>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    task_ids.into_iter().filter_map(|task_id| get_task(connection, task_id).transpose()).collect()
+}
+
+// Returns None if there is no Task with the id
+pub fn get_task(connection : &Connection, task_id : u32) -> rusqlite::Result<Option<Task>> {
+
+    let task = connection.query_row(
+        "SELECT id, name, description, due_date, priority, status FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| Ok(Task {
+            id: TaskId(row.get(0)?),
+            name: row.get(1)?,
+            description: row.get(2)?,
+            due_date: Timestamp(row.get(3)?),
+            priority: get_key(row, 4)?,
+            status: get_key(row, 5)?,
+            ..Task::default()
+        })
+    ).optional()?;
+
+    let Some(mut task) = task else {
+        return Ok(None);
+    };
+
+    let mut statement = connection.prepare("SELECT user_id FROM task_assignees WHERE task_id = ?1 ORDER BY position")?;
+    task.assigned_to = statement.query_map([task_id], |row| Ok(UserId(row.get(0)?)))?.collect::<rusqlite::Result<_>>()?;
+
+    let mut statement = connection.prepare("SELECT id, user_id, message, created_at, edited_at FROM comments WHERE task_id = ?1 ORDER BY id")?;
+    task.comments = statement.query_map([task_id], read_comment)?.collect::<rusqlite::Result<_>>()?;
+
+    Ok(Some(task))
+}
+
+// Reads a column that stores the key of a value, such as the key of a TaskPriority. A key that is not recognised is
+// returned as an error, so that a corrupt row is reported instead of being read as the default value.
+fn get_key<T : FromStr>(row : &Row, index : usize) -> rusqlite::Result<T> {
+    let key : String = row.get(index)?;
+
+    key.parse().map_err(|_| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, format!("{:?} is not a known key", key).into()))
+}
+
+// Reads a comment from a row with the columns id, user_id, message, created_at and edited_at
+fn read_comment(row : &Row) -> rusqlite::Result<Comment> {
+    Ok(Comment {
+        id: row.get(0)?,
+        user_id: UserId(row.get(1)?),
+        message: row.get(2)?,
+        created_at: Timestamp(row.get(3)?),
+        edited_at: row.get::<_, Option<f64>>(4)?.map(Timestamp)
+    })
+}
+
+// Inserts the Task. A Task with the id 0 is given an id greater than the id of every Task created before, including
+// deleted Tasks. Returns None if a Task with the id already exists.
+pub fn create_task(connection : &mut Connection, mut task : Task) -> rusqlite::Result<Option<Task>> {
+
+    let transaction = connection.transaction()?;
+
+    if task.id != TaskId(0) && task_exists(&transaction, task.id.0)? {
+        return Ok(None);
+    }
+
+    insert_task(&transaction, &mut task)?;
+    transaction.commit()?;
+
+    Ok(Some(task))
+}
+
+// Replaces the fields and assigned users of the Task with the same id, and returns the updated Task.
+// The comments of the Task are kept, they are changed with the comment functions so that comments posted by other users
+// since the Task was loaded are not lost. Returns None if there is no Task with the id.
+pub fn update_task(connection : &mut Connection, task : Task) -> rusqlite::Result<Option<Task>> {
+
+    let transaction = connection.transaction()?;
+
+    let updated = transaction.execute(
+        "UPDATE tasks SET name = ?2, description = ?3, due_date = ?4, priority = ?5, status = ?6 WHERE id = ?1",
+        params![task.id.0, task.name, task.description, task.due_date.0, task.priority.key(), task.status.key()]
+    )?;
+
+    if updated == 0 {
+        return Ok(None);
+    }
+
+    transaction.execute("DELETE FROM task_assignees WHERE task_id = ?1", [task.id.0])?;
+    insert_assignees(&transaction, &task)?;
+    transaction.commit()?;
+
+    get_task(connection, task.id.0)
+}
+
+// Returns false if there is no Task with the id
+pub fn delete_task(connection : &Connection, task_id : u32) -> rusqlite::Result<bool> {
+    Ok(connection.execute("DELETE FROM tasks WHERE id = ?1", [task_id])? > 0)
+}
+
+// Adds the comment to the Task with the next comment id. Returns None if there is no Task with the id.
+pub fn create_comment(connection : &mut Connection, task_id : u32, mut comment : Comment) -> rusqlite::Result<Option<Comment>> {
+
+    let transaction = connection.transaction()?;
+
+    if !task_exists(&transaction, task_id)? {
+        return Ok(None);
+    }
+
+    comment.id = transaction.query_row("SELECT COALESCE(MAX(id), 0) + 1 FROM comments WHERE task_id = ?1", [task_id], |row| row.get(0))?;
+
+    insert_comment(&transaction, task_id, &comment)?;
+    transaction.commit()?;
+
+    Ok(Some(comment))
+}
+
+// Replaces the message of the comment and the time it was edited, and returns the updated comment.
+// Returns None if the Task has no comment with the id.
+pub fn update_comment(connection : &Connection, task_id : u32, comment : Comment) -> rusqlite::Result<Option<Comment>> {
+
+    let updated = connection.execute(
+        "UPDATE comments SET message = ?3, edited_at = ?4 WHERE task_id = ?1 AND id = ?2",
+        params![task_id, comment.id, comment.message, comment.edited_at.map(|edited_at| edited_at.0)]
+    )?;
+
+    if updated == 0 {
+        return Ok(None);
+    }
+
+    connection.query_row(
+        "SELECT id, user_id, message, created_at, edited_at FROM comments WHERE task_id = ?1 AND id = ?2",
+        [task_id, comment.id],
+        read_comment
+    ).optional()
+}
+
+// Returns false if the Task has no comment with the id
+pub fn delete_comment(connection : &Connection, task_id : u32, comment_id : u32) -> rusqlite::Result<bool> {
+    Ok(connection.execute("DELETE FROM comments WHERE task_id = ?1 AND id = ?2", [task_id, comment_id])? > 0)
+}
+
+fn task_exists(transaction : &Transaction, task_id : u32) -> rusqlite::Result<bool> {
+    transaction.query_row("SELECT EXISTS (SELECT 1 FROM tasks WHERE id = ?1)", [task_id], |row| row.get(0))
+}
+
+// Inserts the Task with its assignees and comments. A Task with the id 0 is inserted without an id, and is given the id
+// SQLite assigns it.
+fn insert_task(transaction : &Transaction, task : &mut Task) -> rusqlite::Result<()> {
+
+    let id = (task.id != TaskId(0)).then_some(task.id.0);
+
+    transaction.execute(
+        "INSERT INTO tasks (id, name, description, due_date, priority, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![id, task.name, task.description, task.due_date.0, task.priority.key(), task.status.key()]
+    )?;
+
+    task.id = TaskId(transaction.last_insert_rowid() as u32);
+
+    insert_assignees(transaction, task)?;
+
+    for comment in &task.comments {
+        insert_comment(transaction, task.id.0, comment)?;
+    }
+
+    Ok(())
+}
+
+fn insert_assignees(transaction : &Transaction, task : &Task) -> rusqlite::Result<()> {
+
+    for (position, user_id) in task.assigned_to.iter().enumerate() {
+        transaction.execute(
+            "INSERT OR IGNORE INTO task_assignees (task_id, user_id, position) VALUES (?1, ?2, ?3)",
+            params![task.id.0, user_id.0, position]
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_comment(transaction : &Transaction, task_id : u32, comment : &Comment) -> rusqlite::Result<()> {
+    transaction.execute(
+        "INSERT INTO comments (task_id, id, user_id, message, created_at, edited_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![task_id, comment.id, comment.user_id.0, comment.message, comment.created_at.0, comment.edited_at.map(|edited_at| edited_at.0)]
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use todo_core::model::{TaskPriority, TaskStatus};
+
+    use super::*;
+
+    const NOW: Timestamp = Timestamp(1_741_132_800_000.0);
+
+    // An in-memory database seeded with the Tasks of the seed module, which have the ids 1 to 7
+    fn open_seeded() -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        init(&mut connection, NOW).unwrap();
+        connection
+    }
+
+    fn task(id : u32) -> Task {
+        Task {
+            id : TaskId(id),
+            name : "Task".to_string(),
+            assigned_to : vec![UserId(2), UserId(1)],
+            due_date : NOW,
+            priority : TaskPriority::High(),
+            status : TaskStatus::Pending(),
+            ..Task::default()
+        }
+    }
+
+    fn comment(message : &str) -> Comment {
+        Comment { id : 0, user_id : UserId(1), message : message.to_string(), created_at : NOW, edited_at : None }
+    }
+
+    #[test]
+    fn creates_tasks_with_the_next_id() {
+        let mut connection = open_seeded();
+
+        let created_task = create_task(&mut connection, task(0)).unwrap().unwrap();
+
+        assert_eq!(created_task.id, TaskId(8));
+        assert_eq!(get_task(&connection, 8).unwrap(), Some(created_task));
+    }
+
+    #[test]
+    fn does_not_give_new_tasks_the_id_of_a_deleted_task() {
+        let mut connection = open_seeded();
+
+        assert!(delete_task(&connection, 7).unwrap());
+
+        assert_eq!(create_task(&mut connection, task(0)).unwrap().map(|task| task.id), Some(TaskId(8)));
+
+        assert!(delete_task(&connection, 8).unwrap());
+
+        assert_eq!(create_task(&mut connection, task(0)).unwrap().map(|task| task.id), Some(TaskId(9)));
+    }
+
+    #[test]
+    fn migrates_databases_that_gave_deleted_task_ids_to_new_tasks() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        // A version 1 database, with the tasks table that has no AUTOINCREMENT
+        connection.execute_batch(&SCHEMA.replace("id INTEGER PRIMARY KEY AUTOINCREMENT", "id INTEGER PRIMARY KEY")).unwrap();
+        connection.pragma_update(None, "user_version", 1).unwrap();
+        create_task(&mut connection, task(0)).unwrap();
+        create_comment(&mut connection, 1, comment("Kept")).unwrap();
+
+        init(&mut connection, NOW).unwrap();
+
+        assert_eq!(get_task(&connection, 1).unwrap().unwrap().comments.len(), 1);
+
+        assert!(delete_task(&connection, 1).unwrap());
+        assert_eq!(create_task(&mut connection, task(0)).unwrap().map(|task| task.id), Some(TaskId(2)));
+    }
+
+    #[test]
+    fn rejects_tasks_created_with_an_id_that_is_used() {
+        let mut connection = open_seeded();
+
+        assert_eq!(create_task(&mut connection, task(3)).unwrap(), None);
+        assert_eq!(create_task(&mut connection, task(20)).unwrap().map(|task| task.id), Some(TaskId(20)));
+        assert_eq!(create_task(&mut connection, task(0)).unwrap().map(|task| task.id), Some(TaskId(21)));
+    }
+
+    #[test]
+    fn updates_tasks_and_keeps_their_comments() {
+        let mut connection = open_seeded();
+
+        // The Task with the id 6 has three comments, the update does not include them
+        let updated_task = update_task(&mut connection, task(6)).unwrap().unwrap();
+
+        assert_eq!(updated_task.name, "Task");
+        assert_eq!(updated_task.assigned_to, vec![UserId(2), UserId(1)]);
+        assert_eq!(updated_task.priority, TaskPriority::High());
+        assert_eq!(updated_task.comments.len(), 3);
+        assert_eq!(get_task(&connection, 6).unwrap(), Some(updated_task));
+
+        assert_eq!(update_task(&mut connection, task(99)).unwrap(), None);
+    }
+
+    #[test]
+    fn deletes_tasks_with_their_comments() {
+        let connection = open_seeded();
+
+        assert!(delete_task(&connection, 6).unwrap());
+        assert!(!delete_task(&connection, 6).unwrap());
+        assert_eq!(get_task(&connection, 6).unwrap(), None);
+
+        let comment_count : u32 = connection.query_row("SELECT COUNT(*) FROM comments WHERE task_id = 6", [], |row| row.get(0)).unwrap();
+        assert_eq!(comment_count, 0);
+    }
+
+    #[test]
+    fn numbers_comments_per_task() {
+        let mut connection = open_seeded();
+
+        assert_eq!(create_comment(&mut connection, 6, comment("Fourth")).unwrap().map(|comment| comment.id), Some(4));
+        assert_eq!(create_comment(&mut connection, 1, comment("First")).unwrap().map(|comment| comment.id), Some(1));
+        assert_eq!(create_comment(&mut connection, 1, comment("Second")).unwrap().map(|comment| comment.id), Some(2));
+        assert_eq!(create_comment(&mut connection, 99, comment("Lost")).unwrap(), None);
+    }
+
+    #[test]
+    fn updates_and_deletes_comments() {
+        let connection = open_seeded();
+
+        let edited_comment = Comment { id : 2, message : "Edited".to_string(), edited_at : Some(NOW), ..comment("") };
+        let updated_comment = update_comment(&connection, 6, edited_comment).unwrap().unwrap();
+
+        // Only the message and the time it was edited are changed
+        assert_eq!(updated_comment.message, "Edited");
+        assert_eq!(updated_comment.edited_at, Some(NOW));
+        assert_eq!(updated_comment.user_id, UserId(4));
+
+        assert!(delete_comment(&connection, 6, 1).unwrap());
+        assert!(!delete_comment(&connection, 6, 1).unwrap());
+        assert_eq!(update_comment(&connection, 6, Comment { id : 1, ..comment("") }).unwrap(), None);
+
+        let comments = get_task(&connection, 6).unwrap().unwrap().comments;
+        assert_eq!(comments.iter().map(|comment| comment.id).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn reports_tasks_with_an_unknown_status() {
+        let connection = open_seeded();
+
+        connection.execute("UPDATE tasks SET status = 'done' WHERE id = 2", []).unwrap();
+
+        assert!(matches!(get_task(&connection, 2), Err(rusqlite::Error::FromSqlConversionFailure(5, Type::Text, _))));
+        assert!(list_tasks(&connection).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+mod db;
+
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use rusqlite::Connection;
+use tower_http::services::{ServeDir, ServeFile};
+
+use todo_core::model::{Comment, Task, TaskId, User};
+use todo_core::time::Timestamp;
+
+// The REST API used by the client's HttpDataSource, and the built client. The Tasks and Users are stored in a SQLite file,
+// which is created and seeded the first time the server is started.
+//
+// The server is configured with environment variables:
+// ADDRESS        the address the server listens on, 127.0.0.1:3000 by default
+// DATABASE_PATH  the SQLite file, tasks.db by default
+// CLIENT_DIR     the directory of the client built with trunk, dist by default
+#[tokio::main]
+async fn main() {
+
+    let address = env::var("ADDRESS").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let database_path = env::var("DATABASE_PATH").unwrap_or_else(|_| "tasks.db".to_string());
+    let client_dir = env::var("CLIENT_DIR").unwrap_or_else(|_| "dist".to_string());
+
+    let connection = db::open(&database_path, now()).unwrap_or_else(|error| panic!("Failed to open the database {}: {}", database_path, error));
+
+    let state : AppState = Arc::new(Mutex::new(connection));
+
+    let api = Router::new()
+        .route("/tasks", get(list_tasks).post(create_task))
+        .route("/tasks/{id}", get(get_task).put(update_task).delete(delete_task))
+        .route("/tasks/{id}/comments", post(create_comment))
+        .route("/tasks/{id}/comments/{comment_id}", put(update_comment).delete(delete_comment))
+        .route("/users", get(list_users))
+        .with_state(state);
+
+    // Paths that are not files in the client directory are answered with index.html, so the client can handle them
+    let client = ServeDir::new(&client_dir).fallback(ServeFile::new(format!("{}/index.html", client_dir)));
+
+    let app = Router::new()
+        .nest("/api", api)
+        .fallback_service(client);
+
+    let listener = tokio::net::TcpListener::bind(&address).await.unwrap_or_else(|error| panic!("Failed to listen on {}: {}", address, error));
+
+    println!("Serving the app on http://{}", address);
+
+    axum::serve(listener, app).await.expect("Server error");
+}
+
+// The database connection shared by the request handlers
+type AppState = Arc<Mutex<Connection>>;
+
+enum ApiError {
+    NotFound,
+    Conflict,
+    Database(rusqlite::Error)
+}
+
+impl From<rusqlite::Error> for ApiError {
+    fn from(error : rusqlite::Error) -> Self {
+        ApiError::Database(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::NotFound => StatusCode::NOT_FOUND.into_response(),
+            ApiError::Conflict => StatusCode::CONFLICT.into_response(),
+            ApiError::Database(error) => {
+                eprintln!("Database error: {}", error);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+async fn list_tasks(State(state) : State<AppState>) -> Result<Json<Vec<Task>>, ApiError> {
+    let connection = state.lock().unwrap();
+    Ok(Json(db::list_tasks(&connection)?))
+}
+
+async fn get_task(State(state) : State<AppState>, Path(task_id) : Path<u32>) -> Result<Json<Task>, ApiError> {
+    let connection = state.lock().unwrap();
+    db::get_task(&connection, task_id)?.map(Json).ok_or(ApiError::NotFound)
+}
+
+async fn create_task(State(state) : State<AppState>, Json(task) : Json<Task>) -> Result<(StatusCode, Json<Task>), ApiError> {
+    let mut connection = state.lock().unwrap();
+    let task = db::create_task(&mut connection, task)?.ok_or(ApiError::Conflict)?;
+    Ok((StatusCode::CREATED, Json(task)))
+}
+
+async fn update_task(State(state) : State<AppState>, Path(task_id) : Path<u32>, Json(task) : Json<Task>) -> Result<Json<Task>, ApiError> {
+    let mut connection = state.lock().unwrap();
+
+    // The id in the path identifies the Task, the id in the body is ignored
+    let task = Task { id: TaskId(task_id), ..task };
+
+    db::update_task(&mut connection, task)?.map(Json).ok_or(ApiError::NotFound)
+}
+
+async fn delete_task(State(state) : State<AppState>, Path(task_id) : Path<u32>) -> Result<StatusCode, ApiError> {
+    let connection = state.lock().unwrap();
+
+    if db::delete_task(&connection, task_id)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+async fn list_users(State(state) : State<AppState>) -> Result<Json<Vec<User>>, ApiError> {
+    let connection = state.lock().unwrap();
+    Ok(Json(db::list_users(&connection)?))
+}
+
+async fn create_comment(State(state) : State<AppState>, Path(task_id) : Path<u32>, Json(comment) : Json<Comment>) -> Result<(StatusCode, Json<Comment>), ApiError> {
+    let mut connection = state.lock().unwrap();
+    let comment = db::create_comment(&mut connection, task_id, comment)?.ok_or(ApiError::NotFound)?;
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+async fn update_comment(State(state) : State<AppState>, Path((task_id, comment_id)) : Path<(u32, u32)>, Json(comment) : Json<Comment>) -> Result<Json<Comment>, ApiError> {
+    let connection = state.lock().unwrap();
+
+    // The id in the path identifies the comment, the id in the body is ignored
+    let comment = Comment { id: comment_id, ..comment };
+
+    db::update_comment(&connection, task_id, comment)?.map(Json).ok_or(ApiError::NotFound)
+}
+
+async fn delete_comment(State(state) : State<AppState>, Path((task_id, comment_id)) : Path<(u32, u32)>) -> Result<StatusCode, ApiError> {
+    let connection = state.lock().unwrap();
+
+    if db::delete_comment(&connection, task_id, comment_id)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+fn now() -> Timestamp {
+    Timestamp(SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as f64).unwrap_or_default())
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+use todo_core::model::{Comment, Task, TaskId, User};
+
+#[cfg(not(feature = "local-data"))]
+mod http;
+#[cfg(feature = "local-data")]
+mod local;
+
+#[cfg(not(feature = "local-data"))]
+pub use http::HttpDataSource;
+#[cfg(feature = "local-data")]
+pub use local::LocalDataSource;
+
+// The data source used by the app. The REST API is used unless the app is built with the local-data feature,
+// which serves the Tasks from localStorage for offline demos.
+#[cfg(not(feature = "local-data"))]
+pub type AppDataSource = HttpDataSource;
+#[cfg(feature = "local-data")]
+pub type AppDataSource = LocalDataSource;
+
+// The error returned when a data source request fails
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataError {
+    pub message : String,
+    // True if the data source rejected the request because it conflicts with the stored data, such as a Task created
+    // with an id that is already used
+    pub conflict : bool
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// The requests the app makes for its data. The requests are used through Leptos resources, so the app renders
+// loading states while they are pending.
+pub trait DataSource {
+    async fn list_tasks(&self) -> Result<Vec<Task>, DataError>;
+
+    // Returns None if there is no Task with the id
+    async fn get_task(&self, task_id : TaskId) -> Result<Option<Task>, DataError>;
+
+    // Returns the created Task. A Task with the id 0 is given the next id by the data source, and a Task with an id that
+    // is already used is rejected.
+    async fn create_task(&self, task : Task) -> Result<Task, DataError>;
+
+    // Replaces the fields of the Task with the same id and returns the updated Task. The comments of the Task are not
+    // replaced, they are changed with the comment requests so that comments posted by other users are kept.
+    async fn update_task(&self, task : Task) -> Result<Task, DataError>;
+
+    async fn delete_task(&self, task_id : TaskId) -> Result<(), DataError>;
+
+    async fn list_users(&self) -> Result<Vec<User>, DataError>;
+
+    // Adds the comment to the Task and returns the created comment with the id it was given
+    async fn create_comment(&self, task_id : TaskId, comment : Comment) -> Result<Comment, DataError>;
+
+    // Replaces the message of the comment with the same id and returns the updated comment
+    async fn update_comment(&self, task_id : TaskId, comment : Comment) -> Result<Comment, DataError>;
+
+    async fn delete_comment(&self, task_id : TaskId, comment_id : u32) -> Result<(), DataError>;
+}
@@ -0,0 +1,109 @@
+use gloo_net::http::{Request, Response};
+use serde::de::DeserializeOwned;
+
+use todo_core::model::{Comment, Task, TaskId, User};
+use super::{DataError, DataSource};
+
+// The base URL of the REST API. It can be set with the API_BASE_URL environment variable when the app is built,
+// and defaults to the /api path of the server the app is served from.
+const API_BASE_URL : &str = match option_env!("API_BASE_URL") {
+    Some(base_url) => base_url,
+    None => "/api"
+};
+
+// A data source that requests the Tasks and Users from the REST API. Request and response bodies are JSON.
+#[derive(Clone, Copy)]
+pub struct HttpDataSource {
+    base_url : &'static str
+}
+
+impl Default for HttpDataSource {
+    fn default() -> Self {
+        Self::new(API_BASE_URL)
+    }
+}
+
+impl HttpDataSource {
+    pub fn new(base_url : &'static str) -> Self {
+        Self { base_url : base_url.trim_end_matches('/') }
+    }
+
+    fn url(&self, path : &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+impl DataSource for HttpDataSource {
+    async fn list_tasks(&self) -> Result<Vec<Task>, DataError> {
+        let response = Request::get(&self.url("/tasks")).send().await?;
+        read_json(response).await
+    }
+
+    async fn get_task(&self, task_id : TaskId) -> Result<Option<Task>, DataError> {
+        let response = Request::get(&self.url(&format!("/tasks/{}", task_id.0))).send().await?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+
+        read_json(response).await.map(Some)
+    }
+
+    async fn create_task(&self, task : Task) -> Result<Task, DataError> {
+        let response = Request::post(&self.url("/tasks")).json(&task)?.send().await?;
+        read_json(response).await
+    }
+
+    async fn update_task(&self, task : Task) -> Result<Task, DataError> {
+        let response = Request::put(&self.url(&format!("/tasks/{}", task.id.0))).json(&task)?.send().await?;
+        read_json(response).await
+    }
+
+    async fn delete_task(&self, task_id : TaskId) -> Result<(), DataError> {
+        let response = Request::delete(&self.url(&format!("/tasks/{}", task_id.0))).send().await?;
+        check_status(&response)
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, DataError> {
+        let response = Request::get(&self.url("/users")).send().await?;
+        read_json(response).await
+    }
+
+    async fn create_comment(&self, task_id : TaskId, comment : Comment) -> Result<Comment, DataError> {
+        let response = Request::post(&self.url(&format!("/tasks/{}/comments", task_id.0))).json(&comment)?.send().await?;
+        read_json(response).await
+    }
+
+    async fn update_comment(&self, task_id : TaskId, comment : Comment) -> Result<Comment, DataError> {
+        let response = Request::put(&self.url(&format!("/tasks/{}/comments/{}", task_id.0, comment.id))).json(&comment)?.send().await?;
+        read_json(response).await
+    }
+
+    async fn delete_comment(&self, task_id : TaskId, comment_id : u32) -> Result<(), DataError> {
+        let response = Request::delete(&self.url(&format!("/tasks/{}/comments/{}", task_id.0, comment_id))).send().await?;
+        check_status(&response)
+    }
+}
+
+impl From<gloo_net::Error> for DataError {
+    fn from(error : gloo_net::Error) -> Self {
+        DataError { message : error.to_string(), conflict : false }
+    }
+}
+
+// Returns an error if the server did not respond with a success status
+fn check_status(response : &Response) -> Result<(), DataError> {
+    if response.ok() {
+        Ok(())
+    } else {
+        Err(DataError {
+            message : format!("{} returned {} {}", response.url(), response.status(), response.status_text()),
+            conflict : response.status() == 409
+        })
+    }
+}
+
+async fn read_json<T : DeserializeOwned>(response : Response) -> Result<T, DataError> {
+    check_status(&response)?;
+    Ok(response.json::<T>().await?)
+}
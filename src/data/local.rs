@@ -0,0 +1,146 @@
+use gloo_timers::future::TimeoutFuture;
+use todo_core::model::{Comment, Task, TaskId, User, UserDirectory};
+use todo_core::seed::{get_tasks, get_users};
+use todo_core::stored::StoredTask;
+use todo_core::tasks::{insert_task, max_task_id, next_comment_id};
+
+use crate::{now, storage};
+use super::{DataError, DataSource};
+
+// A data source for local development. Tasks are read from localStorage, or from get_tasks() the first time the app is run,
+// and every request is delayed to simulate the latency of a fetch request.
+#[derive(Clone, Copy)]
+pub struct LocalDataSource {
+    latency_ms : u32
+}
+
+impl Default for LocalDataSource {
+    fn default() -> Self {
+        Self { latency_ms : 400 }
+    }
+}
+
+impl LocalDataSource {
+    async fn simulate_latency(&self) {
+        TimeoutFuture::new(self.latency_ms).await;
+    }
+}
+
+impl DataSource for LocalDataSource {
+    async fn list_tasks(&self) -> Result<Vec<Task>, DataError> {
+        self.simulate_latency().await;
+        Ok(load_tasks())
+    }
+
+    async fn get_task(&self, task_id : TaskId) -> Result<Option<Task>, DataError> {
+        Ok(self.list_tasks().await?.into_iter().find(|task| task.id == task_id))
+    }
+
+    async fn create_task(&self, mut task : Task) -> Result<Task, DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+
+        if task.id.0 == 0 {
+            // The sequence is moved past the ids of the stored Tasks, so that Tasks stored before the sequence existed
+            // are not given a duplicate id
+            storage::advance_task_id_sequence(max_task_id(&tasks, &[]));
+            task.id = storage::next_task_id();
+        } else if tasks.iter().any(|existing_task| existing_task.id == task.id) {
+            return Err(DataError { message : format!("{} already exists", task.id), conflict : true });
+        }
+
+        insert_task(&mut tasks, task.clone());
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(task)
+    }
+
+    async fn update_task(&self, task : Task) -> Result<Task, DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+        let existing_task = tasks.iter_mut().find(|existing_task| existing_task.id == task.id).ok_or_else(|| task_not_found(task.id))?;
+        *existing_task = Task { comments : std::mem::take(&mut existing_task.comments), ..task };
+        let updated_task = existing_task.clone();
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(updated_task)
+    }
+
+    async fn delete_task(&self, task_id : TaskId) -> Result<(), DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+        tasks.retain(|task| task.id != task_id);
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(())
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, DataError> {
+        self.simulate_latency().await;
+        Ok(get_users())
+    }
+
+    async fn create_comment(&self, task_id : TaskId, mut comment : Comment) -> Result<Comment, DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+        let task = tasks.iter_mut().find(|task| task.id == task_id).ok_or_else(|| task_not_found(task_id))?;
+        comment.id = next_comment_id(&task.comments);
+        task.comments.push(comment.clone());
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(comment)
+    }
+
+    async fn update_comment(&self, task_id : TaskId, comment : Comment) -> Result<Comment, DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+        let task = tasks.iter_mut().find(|task| task.id == task_id).ok_or_else(|| task_not_found(task_id))?;
+        let existing_comment = task.comments.iter_mut().find(|existing_comment| existing_comment.id == comment.id).ok_or_else(|| comment_not_found(task_id, comment.id))?;
+        existing_comment.message = comment.message;
+        existing_comment.edited_at = comment.edited_at;
+        let updated_comment = existing_comment.clone();
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(updated_comment)
+    }
+
+    async fn delete_comment(&self, task_id : TaskId, comment_id : u32) -> Result<(), DataError> {
+        self.simulate_latency().await;
+
+        let mut tasks = load_tasks();
+        let task = tasks.iter_mut().find(|task| task.id == task_id).ok_or_else(|| task_not_found(task_id))?;
+        task.comments.retain(|comment| comment.id != comment_id);
+        storage::save(storage::TASKS_KEY, &tasks);
+
+        Ok(())
+    }
+}
+
+// Reads the stored Tasks. Tasks stored by earlier versions of the app are migrated to the model, with the users they
+// stored by name found in the directory of this data source. The seed Tasks are saved when nothing is stored yet.
+fn load_tasks() -> Vec<Task> {
+    let users = UserDirectory::new(get_users());
+
+    match storage::load::<Vec<StoredTask>>(storage::TASKS_KEY) {
+        Some(stored_tasks) => stored_tasks.into_iter().map(|stored_task| stored_task.into_task(&users)).collect(),
+        None => {
+            // Saved so that the seed Tasks keep their dates, and are not created again, on the next load
+            let tasks = get_tasks(now());
+            storage::save(storage::TASKS_KEY, &tasks);
+            tasks
+        }
+    }
+}
+
+fn task_not_found(task_id : TaskId) -> DataError {
+    DataError { message : format!("{} was not found", task_id), conflict : false }
+}
+
+fn comment_not_found(task_id : TaskId, comment_id : u32) -> DataError {
+    DataError { message : format!("Comment {} of {} was not found", comment_id, task_id), conflict : false }
+}
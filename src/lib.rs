@@ -0,0 +1,3 @@
+// The parts of the client that do not need the browser, so that they can be used by the benchmarks as well as the app
+
+pub mod task_rows;
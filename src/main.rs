@@ -1,274 +1,1050 @@
+mod data;
+mod mutations;
+mod state;
+mod storage;
+
 use leptos::prelude::*;
+use leptos::ev;
+use leptos::logging;
+use leptos_router::components::{Redirect, Route, Router, Routes};
+use leptos_router::hooks::{use_location, use_params_map};
+use leptos_router::params::ParamsMap;
+use leptos_router::{path, NavigateOptions, ParamSegment, StaticSegment};
 use js_sys::Date;
+use leptos_client_todo_app::task_rows::{TaskRow, TaskRows};
+use data::{AppDataSource, DataSource};
+use mutations::TOAST_DURATION;
+use state::AppState;
+use todo_core::filter::DueRange;
+use todo_core::model::{Comment, Task, TaskId, TaskPriority, TaskStatus, User, UserId};
+use todo_core::panel::{EDIT_TASK_SEGMENT, NEW_TASK_SEGMENT, TASKS_SEGMENT, TASK_ID_PARAM, TRASH_SEGMENT};
+use todo_core::search::Highlighted;
+use todo_core::sort::{SortColumn, SortDirection, SortKey};
+use todo_core::tasks::{sort_comments_by_posted, toggle_assigned_user};
+use todo_core::time::{
+    format_date, format_date_time, format_relative_time, from_date_time_input_values, to_date_input_value, to_time_input_value, TimeZone, Timestamp
+};
+use todo_core::validation::{validate_task, TaskError, TaskField, ValidationRules};
+use todo_core::views::{format_query, SavedView};
+use todo_core::virtual_list::{anchored_scroll_top, scroll_top_to_show, visible_range};
 
 #[component]
 fn App() -> impl IntoView {
 
-    // Boolean signal to determine if Task entry form is opened
-    let (task_form, task_form_writer) = signal(false);
-
-    // A Signal to hold a vector of Tasks
-    let (tasks, tasks_writer) = signal(Vec::<Task>::new());
+    // The panel next to the TaskList follows the URL, so the app is rendered inside the Router
+    view! {
+        <Router>
+            <TaskApp />
+        </Router>
+    }
+}
 
-    // A signal to store the selected Task from the list. Initial value is None as nothing is selected.
-    let (selected_task_id, selected_task_id_writer) = signal(Option::<u32>::None);
+#[component]
+fn TaskApp() -> impl IntoView {
 
-    // The get_tasks() function is used to simulate a fetch request for a list of Tasks
-    // Call to this function will most likely be in a spawn function.
-    for task in get_tasks() {
-        tasks_writer.write().push(task);
-    }
+    // The Tasks, Users and the state of the UI are shared with the components through context
+    let state = AppState::new(AppDataSource::default());
 
-    // A signal to store a counter that is incremeneted and assigned to new Tasks as the Tasks unique id. 
-    // Initial value is the length of the tasks returned from get_tasks()
-    // In a fullstack app, the counter would not be required since we would store the Task in a database and return a unique Task id
-    let (counter, counter_writer) = signal(get_tasks().len() as u32);
+    provide_context(state);
 
     view! {
         <main>
 
             // Renders the add Task button
-            <ActionBar 
-                task_form_writer=task_form_writer
-                selected_task_id_writer=selected_task_id_writer />
-
-            // Renders the tasks store in the tasks singal
-            <TaskList 
-                tasks=tasks 
-                task_form_writer=task_form_writer 
-                selected_task_id=selected_task_id
-                selected_task_id_writer=selected_task_id_writer
-            />
-
-            // Renders the Task details if a task has been selected
-            <Show when=move || { selected_task_id.get().is_some() }>
-                { move || { 
-                    
-                    let task_id = selected_task_id.get().unwrap();
-
-                    view! { 
-                        <TaskDetails
-                            selected_task_id=task_id
-                        /> 
+            <ActionBar />
+
+            // Renders the views the user has saved
+            <SavedViews />
+
+            // Renders the tasks store in the tasks singal, once the tasks_resource has loaded
+            <Suspense fallback=|| view! { <TaskListSkeleton /> }>
+                {move || Suspend::new(async move {
+                    match state.tasks_resource.await {
+                        Ok(_) => view! { <TaskList /> }.into_any(),
+                        Err(error) => view! {
+                            <div class="task-list">
+                                <p class="load-error">{format!("The tasks could not be loaded. {}", error)}</p>
+                            </div>
+                        }.into_any()
                     }
-                }}
-            </Show>
-
-            // Renders the Task entry form if task_form is true
-            // task_form value is set in the ActionBar component
-            <Show when=move || { task_form.get() }>
-                <TaskForm
-                    task_form_writer=task_form_writer 
-                    counter=counter
-                    counter_writer=counter_writer
-                    tasks_writer=tasks_writer
-                />
-            </Show>
+                })}
+            </Suspense>
+
+            // Renders the panel of the route next to the TaskList. The panel is changed through the actions of the AppState,
+            // which navigate to the URL of the panel. A URL that does not match a route renders the NotFound view.
+            // The routes are built from the segments of the Panel paths, which the AppState reads the panel from.
+            <Routes fallback=|| view! { <NotFound /> }>
+                // The query string is kept, so that a link to the app opens the view in it
+                <Route path=path!("/") view=|| {
+                    let query = use_location().search.with_untracked(|search| if search.is_empty() { String::new() } else { format!("?{}", search) });
+                    view! { <Redirect path=format!("/tasks{}", query) options=NavigateOptions { replace: true, ..NavigateOptions::default() } /> }
+                } />
+                <Route path=StaticSegment(TASKS_SEGMENT) view=|| () />
+                <Route path=(StaticSegment(TASKS_SEGMENT), StaticSegment(NEW_TASK_SEGMENT)) view=|| view! { <TaskForm edit_task=None /> } />
+                <Route path=(StaticSegment(TASKS_SEGMENT), ParamSegment(TASK_ID_PARAM)) view=TaskDetailsRoute />
+                <Route path=(StaticSegment(TASKS_SEGMENT), ParamSegment(TASK_ID_PARAM), StaticSegment(EDIT_TASK_SEGMENT)) view=EditTaskRoute />
+                <Route path=StaticSegment(TRASH_SEGMENT) view=TrashList />
+            </Routes>
+
+            // Renders the errors of the changes that could not be saved
+            <Toasts />
         </main>
     }
 }
 
 #[component]
-fn ActionBar(
-    task_form_writer : WriteSignal<bool>, 
-    selected_task_id_writer : WriteSignal<Option<u32>>) -> impl IntoView {
+fn ActionBar() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let users = state.users;
+    let current_user = state.current_user;
 
     view! {
         <div class="action-bar">
-            <button on:click=move |_| {
-                task_form_writer.set(true);
-                selected_task_id_writer.set(None);
-            }>
+            <button title="New task" on:click=move |_| state.open_new_task_form()>
                 <svg xmlns="http://www.w3.org/2000/svg" height="40" viewBox="0 -960 960 960" width="40"><path d="M200-200h43.923l427.923-427.923-43.923-43.923L200-243.923V-200Zm-40 40v-100.769l527.231-527.77q6.146-5.481 13.573-8.471 7.427-2.99 15.486-2.99 8.06 0 15.616 2.538 7.556 2.539 13.94 9.154l42.693 42.923q6.615 6.385 9.038 14.008Q800-723.754 800-716.131q0 8.131-2.741 15.558-2.74 7.427-8.72 13.573l-527.77 527H160Zm600.769-556.308-44.461-44.461 44.461 44.461Zm-111.27 66.809-21.576-22.347 43.923 43.923-22.347-21.576Z"></path></svg>
             </button>
+            <button title="Trash" on:click=move |_| state.open_trash()>
+                <svg xmlns="http://www.w3.org/2000/svg" height="40" viewBox="0 -960 960 960" width="40"><path d="M280-120q-33 0-56.5-23.5T200-200v-520h-40v-80h200v-40h240v40h200v80h-40v520q0 33-23.5 56.5T680-120H280Zm400-600H280v520h400v-520ZM360-280h80v-360h-80v360Zm160 0h80v-360h-80v360ZM280-720v520-520Z"></path></svg>
+            </button>
+
+            // Renders the current user's avatar at the bottom of the bar, opening it lists the users to switch to
+            <details class="current-user">
+                <summary title=move || format!("Signed in as {}", users.map(|users| users.name(current_user.get())).unwrap_or_default())>
+                    <img src=move || users.map(|users| users.avatar(current_user.get())).unwrap_or_default() />
+                </summary>
+                <ul>
+                    {move || {
+                        users.get().unwrap_or_default().iter().map(|user| {
+
+                            let user_id = user.id;
+                            let image = users.map(|users| users.avatar(user_id)).unwrap_or_default();
+                            let name = user.name.clone();
+
+                            view! {
+                                <li on:click=move |e| {
+                                    state.set_current_user(user_id);
+
+                                    // Close the list once a user is picked
+                                    if let Some(details) = event_target::<web_sys::Element>(&e).closest("details").ok().flatten() {
+                                        let _ = details.remove_attribute("open");
+                                    }
+                                }>
+                                    <img src={image} />
+                                    <span>{name}</span>
+                                </li>
+                            }
+                        }).collect_view()
+                    }}
+                </ul>
+            </details>
         </div>
     }
 }
 
+// The height of a TaskList row in pixels. Every row has the same height, so the rows in the viewport can be found from the scroll position.
+const TASK_ROW_HEIGHT: f64 = 66.0;
+
+// The number of rows rendered above and below the viewport
+const TASK_ROW_BUFFER: usize = 10;
+
 #[component]
-fn TaskList(
-    tasks : ReadSignal<Vec<Task>>, 
-    task_form_writer : WriteSignal<bool>, 
-    selected_task_id : ReadSignal<Option<u32>>,
-    selected_task_id_writer : WriteSignal<Option<u32>>) -> impl IntoView {
+fn TaskList() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+
+    // The rows are keyed by the Task id. A row is only rendered when a Task is added, and it updates itself from its TaskRow signals
+    // when the Task is changed or selected. Only the Tasks that match the filters are listed, in the order the TaskList is sorted in.
+    let rows = TaskRows::new(state.selected_task_id());
+    let sync_rows = move || state.with_listed_tasks(|tasks| rows.sync(tasks));
+
+    // The rows are added straight away, so the list is not rendered empty before the effect has run
+    untrack(sync_rows);
+
+    Effect::new(move |_| sync_rows());
+    Effect::new(move |_| rows.select(state.selected_task_id()));
+    Effect::new(move |_| rows.set_pending(state.mutations.pending_task_ids()));
+
+    // The list is virtualized, only the rows in the viewport and a buffer around it are rendered. The rows above and below
+    // are replaced by spacers, so the list can be scrolled as if every row was rendered.
+    let list_ref = NodeRef::<leptos::html::Div>::new();
+    let (scroll_top, scroll_top_writer) = signal(0.0);
+    let (viewport_height, viewport_height_writer) = signal(window().inner_height().ok().and_then(|height| height.as_f64()).unwrap_or_default());
+
+    let measure_viewport = move || {
+        if let Some(list) = list_ref.get_untracked() {
+            viewport_height_writer.set(list.client_height() as f64);
+        }
+    };
+
+    Effect::new(move |_| {
+        if list_ref.get().is_some() {
+            measure_viewport();
+        }
+    });
+
+    let resize_listener = window_event_listener(ev::resize, move |_| measure_viewport());
+    on_cleanup(move || resize_listener.remove());
+
+    let scroll_to = move |top : f64| {
+        if let Some(list) = list_ref.get_untracked() {
+            list.set_scroll_top(top as i32);
+        }
+        scroll_top_writer.set(top);
+    };
+
+    let visible_rows = Memo::new(move |_| {
+        rows.ids().with(|ids| visible_range(ids.len(), TASK_ROW_HEIGHT, scroll_top.get(), viewport_height.get(), TASK_ROW_BUFFER))
+    });
+
+    // When the list changes, the rows at the top of the viewport are kept in place, so that filtering the list or adding a Task
+    // does not move the rows the user is looking at
+    Effect::new(move |previous_ids : Option<Vec<TaskId>>| {
+        let ids = rows.ids().get();
+
+        if let Some(previous_ids) = previous_ids {
+            let top = anchored_scroll_top(&previous_ids, &ids, TASK_ROW_HEIGHT, scroll_top.get_untracked());
+
+            if top != scroll_top.get_untracked() {
+                scroll_to(top);
+            }
+        }
+
+        ids
+    });
+
+    // The selected row is scrolled into view, e.g. when it is selected with the keyboard
+    Effect::new(move |_| {
+        let Some(task_id) = state.selected_task_id() else {
+            return;
+        };
+
+        if let Some(index) = rows.ids().with_untracked(|ids| ids.iter().position(|id| *id == task_id)) {
+            if let Some(top) = scroll_top_to_show(index, TASK_ROW_HEIGHT, scroll_top.get_untracked(), viewport_height.get_untracked()) {
+                scroll_to(top);
+            }
+        }
+    });
+
+    // The list keeps the keyboard focus while the rows are rendered and removed. The arrow, page, home and end keys move the
+    // selection, and the selected row is announced to screen readers as the active descendant of the list.
+    let on_keydown = move |e : ev::KeyboardEvent| {
+
+        // Keys pressed in the selects of a row are left to the selects
+        if e.target() != e.current_target() {
+            return;
+        }
+
+        let page_rows = ((viewport_height.get_untracked() / TASK_ROW_HEIGHT).floor() as usize).max(1);
+
+        let next_task_id = rows.ids().with_untracked(|ids| {
+            let last = ids.len().checked_sub(1)?;
+            let selected = state.selected_task_id().and_then(|task_id| ids.iter().position(|id| *id == task_id));
+
+            // Without a selection, the moving keys select the first row in the viewport
+            let first_visible = ((scroll_top.get_untracked() / TASK_ROW_HEIGHT).ceil() as usize).min(last);
+
+            let index = match (e.key().as_str(), selected) {
+                ("Home", _) => 0,
+                ("End", _) => last,
+                ("ArrowDown" | "ArrowUp" | "PageDown" | "PageUp", None) => first_visible,
+                ("ArrowDown", Some(index)) => (index + 1).min(last),
+                ("ArrowUp", Some(index)) => index.saturating_sub(1),
+                ("PageDown", Some(index)) => (index + page_rows).min(last),
+                ("PageUp", Some(index)) => index.saturating_sub(page_rows),
+                _ => return None
+            };
+
+            Some(ids[index])
+        });
+
+        if let Some(task_id) = next_task_id {
+            e.prevent_default();
+            state.move_selection(task_id);
+        }
+    };
 
     view! {
-        <div class="task-list">
-            {move || {
-                tasks.get().into_iter().map(|task :Task| {
-
-                    let due_date = task.due_date;
-
-                    let cloned_task1 = task.clone();
-                    
-                    // Determine if the task is selected and apply css to highlight the row.
-                    let item_css = {
-                        if selected_task_id.get() == Some(task.id) {
-                            "item selected-item"
-                        }else{
-                            "item"
+        <div class="task-pane">
+            <FilterBar listed_task_count=Signal::derive(move || rows.ids().with(Vec::len)) />
+            <TaskListHeader />
+            <Show when=move || rows.ids().with(Vec::is_empty) && state.tasks.with(|tasks| !tasks.is_empty())>
+                <p class="task-list-empty">"No tasks match the filters."</p>
+            </Show>
+            <div
+                class="task-list"
+                node_ref=list_ref
+                tabindex="0"
+                role="listbox"
+                aria-label="Tasks"
+                aria-activedescendant=move || state.selected_task_id().map(task_row_element_id)
+                on:scroll=move |_| {
+                    if let Some(list) = list_ref.get_untracked() {
+                        scroll_top_writer.set(list.scroll_top() as f64);
+                    }
+                }
+                on:keydown=on_keydown
+            >
+                <div class="task-list-spacer" style:height=move || format!("{}px", visible_rows.get().start as f64 * TASK_ROW_HEIGHT)></div>
+                <For
+                    each=move || {
+                        let visible_rows = visible_rows.get();
+                        rows.ids().with(|ids| ids[visible_rows].to_vec())
+                    }
+                    key=|task_id| *task_id
+                    children=move |task_id| rows.row(task_id).map(|row| view! { <TaskListRow row=row /> })
+                />
+                <div class="task-list-spacer" style:height=move || {
+                    format!("{}px", rows.ids().with(|ids| ids.len() - visible_rows.get().end) as f64 * TASK_ROW_HEIGHT)
+                }></div>
+            </div>
+        </div>
+    }
+}
+
+// The views the user has saved, listed in a sidebar with a form to save the current view
+#[component]
+fn SavedViews() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+
+    // A signal to store the name the current view is saved with
+    let (name, name_writer) = signal(String::new());
+
+    let save = move || {
+        state.save_current_view(&name.get_untracked());
+        name_writer.set(String::new());
+    };
+
+    view! {
+        <nav class="saved-views">
+            <h2>"Views"</h2>
+            <ul>
+                {move || {
+                    state.saved_views.get().into_iter().map(|saved_view| {
+                        let SavedView { name, view } = saved_view;
+
+                        // The link holds the view in its query string, so that it can be copied and shared
+                        let href = match format_query(&view.query_params(&LocalTimeZone)) {
+                            query if query.is_empty() => "?".to_string(),
+                            query => query
+                        };
+
+                        let is_open = { let view = view.clone(); move || state.current_view() == view };
+                        let delete = { let name = name.clone(); move |_| state.delete_saved_view(&name) };
+
+                        view! {
+                            <li class:open-view=is_open>
+                                <a href=href on:click=move |e| {
+                                    e.prevent_default();
+                                    state.open_view(view.clone());
+                                }>{name}</a>
+                                <button class="saved-view-delete" title="Delete view" on:click=delete>"\u{00D7}"</button>
+                            </li>
                         }
-                    };
+                    }).collect_view()
+                }}
+            </ul>
+            // Saving with the name of a saved view replaces that view
+            <div class="saved-view-form">
+                <input type="text" placeholder="Name this view" prop:value=move || name.get()
+                    on:input=move |e| name_writer.set(event_target_value(&e))
+                    on:keydown=move |e| if e.key() == "Enter" && !name.with_untracked(|name| name.trim().is_empty()) { save() }
+                />
+                <button disabled=move || name.with(|name| name.trim().is_empty()) on:click=move |_| save()>"Save view"</button>
+            </div>
+        </nav>
+    }
+}
+
+// The filters of the TaskList. The Tasks are filtered by the picked statuses, priorities, assigned users and due dates, and
+// a Task is listed if it matches all of them. The number of Tasks that match is shown next to the filters.
+#[component]
+fn FilterBar(listed_task_count : Signal<usize>) -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let filter = state.filter;
+
+    // The dates of the custom due range, as date input values
+    let custom_due_date = move |pick_to : bool| filter.with(|filter| match filter.due {
+        Some(DueRange::Custom { from, to }) => if pick_to { to } else { from },
+        _ => None
+    }.map(|date| to_date_input_value(date, &LocalTimeZone)).unwrap_or_default());
+
+    let set_custom_due_date = move |pick_to : bool, value : String| {
+        let date = from_date_time_input_values(&value, "", &LocalTimeZone);
+
+        let (from, to) = match filter.get_untracked().due {
+            Some(DueRange::Custom { from, to }) => (from, to),
+            _ => (None, None)
+        };
+
+        state.set_due_filter(Some(if pick_to { DueRange::Custom { from, to: date } } else { DueRange::Custom { from: date, to } }));
+    };
 
-                    let priority = match task.priority {
-                        TaskPriority::Low() => "cell task-priority task-priority-low",
-                        TaskPriority::Medium() => "cell task-priority task-priority-medium",
-                        TaskPriority::High() => "cell task-priority task-priority-high",
+    view! {
+        <div class="filter-bar">
+            <input
+                type="search"
+                class="filter-search"
+                placeholder="Search tasks"
+                prop:value=state.search
+                on:input=move |e| state.set_search(event_target_value(&e))
+            />
+
+            <FilterMenu label="Status" picked_count=Signal::derive(move || filter.with(|filter| filter.statuses.len()))>
+                {
+                    TaskStatus::ALL.into_iter().map(|status| {
+                        let label = status.label();
+                        let checked = Signal::derive({ let status = status.clone(); move || filter.with(|filter| filter.statuses.contains(&status)) });
+                        view! { <FilterOption label=label checked=checked on_toggle=move || state.toggle_status_filter(status.clone()) /> }
+                    }).collect_view()
+                }
+            </FilterMenu>
+
+            <FilterMenu label="Priority" picked_count=Signal::derive(move || filter.with(|filter| filter.priorities.len()))>
+                {
+                    TaskPriority::ALL.into_iter().map(|priority| {
+                        let label = priority.label();
+                        let checked = Signal::derive({ let priority = priority.clone(); move || filter.with(|filter| filter.priorities.contains(&priority)) });
+                        view! { <FilterOption label=label checked=checked on_toggle=move || state.toggle_priority_filter(priority.clone()) /> }
+                    }).collect_view()
+                }
+            </FilterMenu>
+
+            <FilterMenu label="Assignee" picked_count=Signal::derive(move || filter.with(|filter| filter.assignees.len()))>
+                {move || {
+                    state.users.get().unwrap_or_default().iter().map(|user| {
+                        let user_id = user.id;
+                        let checked = Signal::derive(move || filter.with(|filter| filter.assignees.contains(&user_id)));
+                        view! { <FilterOption label=user.name.clone() checked=checked on_toggle=move || state.toggle_assignee_filter(user_id) /> }
+                    }).collect_view()
+                }}
+            </FilterMenu>
+
+            <select class="filter-due" on:change=move |e| {
+                let key = event_target_value(&e);
+
+                let due = match key.as_str() {
+                    "custom" => Some(DueRange::Custom { from: None, to: None }),
+                    key => DueRange::PRESETS.into_iter().find(|due| due.key() == key)
+                };
+
+                state.set_due_filter(due);
+            }>
+                <option value="" prop:selected=move || filter.with(|filter| filter.due.is_none())>"Any due date"</option>
+                {
+                    DueRange::PRESETS.into_iter().chain([DueRange::Custom { from: None, to: None }]).map(|due| {
+                        let key = due.key();
+                        view! {
+                            <option value=key prop:selected=move || filter.with(|filter| filter.due.is_some_and(|picked| picked.key() == key))>{due.label()}</option>
+                        }
+                    }).collect_view()
+                }
+            </select>
+
+            // An empty date leaves that end of the custom range open
+            <Show when=move || filter.with(|filter| matches!(filter.due, Some(DueRange::Custom { .. })))>
+                <div class="filter-dates">
+                    <input type="date" title="Due from" prop:value=move || custom_due_date(false) on:change=move |e| set_custom_due_date(false, event_target_value(&e)) />
+                    <span>"to"</span>
+                    <input type="date" title="Due to" prop:value=move || custom_due_date(true) on:change=move |e| set_custom_due_date(true, event_target_value(&e)) />
+                </div>
+            </Show>
+
+            <span class="filter-count">
+                {move || format!("{} of {} tasks", listed_task_count.get(), state.tasks.with(Vec::len))}
+            </span>
+
+            <Show when=move || filter.with(|filter| !filter.is_empty())>
+                <button class="filter-clear" on:click=move |_| state.clear_filter()>"Clear filters"</button>
+            </Show>
+        </div>
+    }
+}
+
+// A dropdown of the values a filter can pick. The label shows how many values are picked.
+#[component]
+fn FilterMenu(label : &'static str, picked_count : Signal<usize>, children : Children) -> impl IntoView {
+    view! {
+        <details class="filter-menu" class:filter-menu-active={move || picked_count.get() > 0}>
+            <summary>
+                {label}
+                {move || (picked_count.get() > 0).then(|| format!(" ({})", picked_count.get()))}
+            </summary>
+            <ul>
+                {children()}
+            </ul>
+        </details>
+    }
+}
+
+#[component]
+fn FilterOption<F>(#[prop(into)] label : String, checked : Signal<bool>, on_toggle : F) -> impl IntoView
+where
+    F: Fn() + 'static {
+    view! {
+        <li>
+            <label>
+                <input type="checkbox" prop:checked=checked on:change=move |_| on_toggle() />
+                {label}
+            </label>
+        </li>
+    }
+}
+
+// The column headers of the TaskList. Clicking a header sorts the Tasks by the column, and clicking it again reverses the order.
+// Shift-clicking a header sorts the Tasks by the column after the columns they are already sorted by.
+#[component]
+fn TaskListHeader() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+
+    view! {
+        <div class="task-list-header">
+            {
+                SortColumn::ALL.into_iter().map(|column| {
+
+                    // Search results are listed by relevance, so the sort is not shown while searching. It applies again once the search is cleared.
+                    let sort_key = move || if state.is_searching() {
+                        None
+                    } else {
+                        state.sort.with(|sort| sort.key(column).map(|(index, key)| (index, key, sort.keys.len())))
                     };
 
-                    let (status, status_class) = match task.status {
-                        TaskStatus::New() => ("new", "task-attribute task-status-new"),
-                        TaskStatus::Pending() => ("pending", "task-attribute task-status-pending"),
-                        TaskStatus::InProgress() => ("in progress", "task-attribute task-status-inprogress"),
-                        TaskStatus::Complete() => ("Complete", "task-attribute task-status-complete"),
+                    // The arrow shows the direction, and the position of the column among the keys when there are several
+                    let indicator = move || sort_key().map(|(index, key, key_count)| {
+                        let arrow = match key.direction {
+                            SortDirection::Ascending() => "\u{25B2}",
+                            SortDirection::Descending() => "\u{25BC}",
+                        };
+
+                        if key_count > 1 { format!("{}{}", arrow, index + 1) } else { arrow.to_string() }
+                    });
+
+                    let aria_sort = move || match sort_key() {
+                        Some((_, SortKey { direction: SortDirection::Ascending(), .. }, _)) => "ascending",
+                        Some((_, SortKey { direction: SortDirection::Descending(), .. }, _)) => "descending",
+                        None => "none"
                     };
 
                     view! {
-                        <div class={item_css} on:click= move |_| {
-                            // When the row is selected, the task's id is stored and the TaskDetails component is rendered 
-                            selected_task_id_writer.set(Some(task.id));
-                            // In case the TaskForm component is already rendered, we want to remove it so that the TaskDetails component can be rendered. 
-                            // TaskForm and TaskDetails components should not be rendered at the same time. 
-                            task_form_writer.set(false);
-                        }>
-                            <h4 class="cell">{task.id}</h4>
-                            <i class={priority}></i>
-                            <h3 class="cell">{task.name}</h3>
-                            <AssignedUsers task=cloned_task1 />
-                            <span class="cell width-100-px">{format_date(due_date)}</span>
-                            <div class={status_class}><span>{status}</span></div>
-                        </div>
+                        <button
+                            class=format!("sort-header sort-header-{}", column.key())
+                            class:sorted=move || sort_key().is_some()
+                            aria-sort=aria_sort
+                            title=format!("Sort by {}. Shift-click to sort by it next.", column.label())
+                            on:click=move |e| if e.shift_key() { state.then_by(column) } else { state.sort_by(column) }
+                        >
+                            <span>{column.label()}</span>
+                            <span class="sort-indicator">{indicator}</span>
+                        </button>
                     }
                 }).collect_view()
-            }}
+            }
         </div>
     }
 }
 
+// The id of a TaskList row element, which the list refers to as its active descendant
+fn task_row_element_id(task_id : TaskId) -> String {
+    format!("task-row-{}", task_id.0)
+}
+
 #[component]
-fn TaskDetails(selected_task_id : u32) -> impl IntoView {
+fn TaskListRow(row : TaskRow) -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let mutations = state.mutations;
+    let task_id = row.task_id;
+
+    // The row's signals are derived further, so that the priority and status selects only update when their value changes
+    let task = RwSignal::from(row.task).read_only();
+    let is_selected = RwSignal::from(row.is_selected).read_only();
+    let is_pending = RwSignal::from(row.is_pending).read_only();
+    let priority = Memo::new(move |_| task.with(|task| task.priority.clone()));
+    let status = Memo::new(move |_| task.with(|task| task.status.clone()));
+
+    // While the Tasks are searched, the matches in the name are highlighted, and a snippet of the description or a comment
+    // shows the matches found there
+    let name = move || state.search_query.with(|query| task.with(|task| Highlighted { ranges: query.highlights(&task.name), text: task.name.clone() }));
+    let snippet = move || state.search_query.with(|query| task.with(|task| query.snippet(task)));
+
+    // Determine if the task is selected and apply css to highlight the row.
+    // Rows with changes that are waiting for the data source are dimmed until the changes are saved.
+    let item_css = move || {
+        match (is_selected.get(), is_pending.get()) {
+            (true, true) => "item selected-item pending-item",
+            (true, false) => "item selected-item",
+            (false, true) => "item pending-item",
+            (false, false) => "item"
+        }
+    };
+
+    view! {
+        <div
+            class=item_css
+            id=task_row_element_id(task_id)
+            role="option"
+            aria-selected=move || is_selected.get().to_string()
+            style:height=format!("{}px", TASK_ROW_HEIGHT)
+            // When the row is selected, the TaskDetails component is rendered in place of the TaskForm or the trash
+            on:click=move |_| state.select_task(task_id)
+        >
+            <h4 class="cell">{task_id.to_string()}</h4>
+            // The priority is changed by the select that covers the priority dot
+            <i class=move || format!("cell task-priority {}", priority.with(|priority| priority.css_class())) title=move || priority.with(|priority| priority.label())>
+                <TaskPrioritySelect 
+                    priority=priority
+                    on_change=move |priority| mutations.update_task(task_id, "change the priority of", |task| task.priority = priority)
+                />
+            </i>
+            <div class="cell task-name">
+                <h3>{move || highlighted_text(name())}</h3>
+                {move || snippet().map(|snippet| view! { <p class="task-snippet">{highlighted_text(snippet)}</p> })}
+            </div>
+            {move || view! { <AssignedUsers assigned_to=task.with(|task| task.assigned_to.clone()) /> }}
+            <span class="cell width-100-px">{move || format_date(task.with(|task| task.due_date), &LocalTimeZone)}</span>
+            <div class=move || format!("task-attribute {}", status.with(|status| status.css_class()))>
+                <TaskStatusSelect 
+                    status=status
+                    on_change=move |status| mutations.update_task(task_id, "change the status of", |task| task.status = status)
+                />
+            </div>
+            <button class="row-action" title="Move to trash" on:click=move |e| {
+                // Stop the click from selecting the row that is being removed
+                e.stop_propagation();
 
-    // The get_task() function simulates a fetch request for a single Task using the selected task id.
-    // Call to this function will most likely be in a spawn function.
-    let task = get_task(selected_task_id); 
+                state.move_task_to_trash(task_id);
+            }>
+                <svg xmlns="http://www.w3.org/2000/svg" height="20" viewBox="0 -960 960 960" width="20"><path d="M280-120q-33 0-56.5-23.5T200-200v-520h-40v-80h200v-40h240v40h200v80h-40v520q0 33-23.5 56.5T680-120H280Zm400-600H280v520h400v-520ZM360-280h80v-360h-80v360Zm160 0h80v-360h-80v360ZM280-720v520-520Z"></path></svg>
+            </button>
+        </div>
+    }
+}
 
-    // Clone the task so that the clone can be passed to the AssignedUsers component 
-    let clone_task = task.clone();  
+// Renders the text with its matches wrapped in mark elements
+fn highlighted_text(highlighted : Highlighted) -> impl IntoView {
+    let mut parts = Vec::new();
+    let mut end = 0;
 
-    let (priority, priority_class) = match task.priority {
-        TaskPriority::Low() => ("Low", "task-attribute task-priority-low"),
-        TaskPriority::Medium() => ("Medium", "task-attribute task-priority-medium"),
-        TaskPriority::High() => ("High", "task-attribute task-priority-high"),
-    };
+    for range in highlighted.ranges {
+        parts.push(highlighted.text[end..range.start].to_string().into_any());
+        parts.push(view! { <mark>{highlighted.text[range.clone()].to_string()}</mark> }.into_any());
+        end = range.end;
+    }
 
-    let (status, status_class) = match task.status {
-        TaskStatus::New() => ("new", "task-attribute task-status-new"),
-        TaskStatus::Pending() => ("pending", "task-attribute task-status-pending"),
-        TaskStatus::InProgress() => ("in progress", "task-attribute task-status-inprogress"),
-        TaskStatus::Complete() => ("Complete", "task-attribute task-status-complete"),
-    };
+    parts.push(highlighted.text[end..].to_string().into_any());
+    parts
+}
+
+// The id of the Task in the path of a route, or None if the id is not a number
+fn task_id_param(params : Memo<ParamsMap>) -> Option<TaskId> {
+    params.with(|params| params.get(TASK_ID_PARAM)).and_then(|id| id.parse().ok()).map(TaskId)
+}
+
+// Renders the TaskDetails of /tasks/:id
+#[component]
+fn TaskDetailsRoute() -> impl IntoView {
+
+    let params = use_params_map();
+
+    // The route keeps its view when another Task is opened, so the details are rebuilt when the id changes
+    let task_id = Memo::new(move |_| task_id_param(params));
+
+    move || match task_id.get() {
+        Some(task_id) => view! { <TaskDetails selected_task_id=task_id /> }.into_any(),
+        None => view! { <NotFound /> }.into_any()
+    }
+}
+
+// Renders the TaskForm of /tasks/:id/edit, pre-filled with the Task
+#[component]
+fn EditTaskRoute() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let params = use_params_map();
+
+    // The id of the Task being edited, or None if there is no Task with the id. The form is only rebuilt when the id changes,
+    // so that changes made to the Task while it is edited do not reset the form.
+    let edit_task_id = Memo::new(move |_| {
+        task_id_param(params).filter(|task_id| state.tasks.with(|tasks| tasks.iter().any(|task| task.id == *task_id)))
+    });
+
+    move || {
+        if !state.tasks_loaded.get() {
+            return ().into_any();
+        }
 
+        match edit_task_id.get() {
+            Some(task_id) => {
+                let edit_task = state.tasks.with_untracked(|tasks| tasks.iter().find(|task| task.id == task_id).cloned());
+                view! { <TaskForm edit_task=edit_task /> }.into_any()
+            },
+            None => view! { <NotFound /> }.into_any()
+        }
+    }
+}
+
+// Rendered in place of the panel for a URL that does not match a route, or for a Task that does not exist
+#[component]
+fn NotFound() -> impl IntoView {
     view! {
         <div class="form-container">
             <div class="row">
-                <h2>{task.name}</h2>
-                <footer>
-                    <ul>
-                        <li>
-                            <div class={priority_class}>
-                                <label>Priority</label>
-                                <span>{priority}</span>
-                            </div>
-                        </li>
-                        <li>
-                            <div class={status_class}>
-                                <label>Status</label>
-                                <span>{status}</span>
-                            </div>
-                        </li>
-                        <li>
-                            <div class="task-attribute">
-                                <label>Due Date</label>
-                                <span>{format_date(task.due_date)}</span>
-                            </div>
-                        </li>
-                    </ul>
-                </footer>
+                <h2>"Not found"</h2>
+                <p>"There is no task or page at this address. The task may have been removed."</p>
             </div>
+        </div>
+    }
+}
 
-            <div class="row">
-                <p>{task.description}</p>
+#[component]
+fn TaskDetails(selected_task_id : TaskId) -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let mutations = state.mutations;
+
+    // Find the selected Task in the same tasks signal that the TaskList renders, so that edits to the Task are shown immediately.
+    // The Memo is None if the Task no longer exists in the list.
+    let task = Memo::new(move |_| {
+        state.tasks.with(|tasks| tasks.iter().find(|task| task.id == selected_task_id).cloned())
+    });
+
+    // The view is only rebuilt when the Task is removed or restored, the parts of the view that show the Task's
+    // values update on their own so that opened inputs, such as the AssignUserList, keep their state when the Task changes.
+    let task_exists = Memo::new(move |_| task.with(Option::is_some));
+
+    // A resource to request the selected Task. The details are rendered once the request has completed, and the Task in the
+    // tasks signal is replaced with the requested Task once. The requested Task is dropped if the Task has been changed since
+    // the request was started, since it may not include the change.
+    let task_resource = LocalResource::new(move || async move {
+        let request_mark = mutations.request_mark();
+        (request_mark, mutations.source.get_task(selected_task_id).await)
+    });
+    let applied = StoredValue::new(false);
+
+    Effect::new(move |_| {
+        match task_resource.get() {
+            Some((request_mark, Ok(Some(requested_task)))) if !applied.get_value() => {
+                applied.set_value(true);
+                mutations.apply_requested_task(requested_task, request_mark);
+            },
+            Some((_, Err(error))) => logging::error!("Failed to load task {}: {}", selected_task_id, error),
+            _ => ()
+        }
+    });
+
+    let details = move || {
+        // A link to a Task is not shown as not found while the Tasks are loading
+        if !task_exists.get() {
+            return if state.tasks_loaded.get() { view! { <NotFound /> }.into_any() } else { ().into_any() };
+        }
+
+        let selected_task = Signal::derive(move || task.get().unwrap_or_default());
+
+        view! {
+            <div class="form-container">
+                <div class="row">
+                    <h2>{move || selected_task.with(|task| task.name.clone())}</h2>
+                    <footer>
+                        <ul>
+                            <li>
+                                <div class={move || format!("task-attribute {}", selected_task.with(|task| task.priority.css_class()))}>
+                                    <label>Priority</label>
+                                    <TaskPrioritySelect 
+                                        priority=Signal::derive(move || selected_task.with(|task| task.priority.clone()))
+                                        on_change=move |priority| mutations.update_task(selected_task_id, "change the priority of", |task| task.priority = priority)
+                                    />
+                                </div>
+                            </li>
+                            <li>
+                                <div class={move || format!("task-attribute {}", selected_task.with(|task| task.status.css_class()))}>
+                                    <label>Status</label>
+                                    <TaskStatusSelect 
+                                        status=Signal::derive(move || selected_task.with(|task| task.status.clone()))
+                                        on_change=move |status| mutations.update_task(selected_task_id, "change the status of", |task| task.status = status)
+                                    />
+                                </div>
+                            </li>
+                            <li>
+                                <div class="task-attribute">
+                                    <label>Due Date</label>
+                                    <span>{move || format_date(selected_task.with(|task| task.due_date), &LocalTimeZone)}</span>
+                                </div>
+                            </li>
+                            <li>
+                                // Open the TaskForm pre-filled with this Task
+                                <button on:click=move |_| state.open_edit_task_form(selected_task_id)>
+                                    "Edit Task"
+                                </button>
+                            </li>
+                            <li>
+                                <button class="button-danger" on:click=move |_| state.move_task_to_trash(selected_task_id)>
+                                    "Delete Task"
+                                </button>
+                            </li>
+                        </ul>
+                    </footer>
+                </div>
+
+                <div class="row">
+                    <p>{move || selected_task.with(|task| task.description.clone())}</p>
+                </div>
+
+                <div class="row">
+                    <AssignUserList
+                        assigned_to=Signal::derive(move || selected_task.with(|task| task.assigned_to.clone()))
+                        on_toggle=move |user_id| mutations.update_task(selected_task_id, "change the assigned users of", |task| toggle_assigned_user(&mut task.assigned_to, user_id))
+                    />
+                </div>
+
+                <TaskComments
+                    task_id=selected_task_id
+                    comments=Signal::derive(move || selected_task.with(|task| task.comments.clone()))
+                />
             </div>
+        }.into_any()
+    };
 
-            <div class="row">
-                <AssignedUsers task=clone_task />
+    view! {
+        <Suspense fallback=|| view! { <TaskDetailsSkeleton /> }>
+            {move || Suspend::new(async move {
+                // A failed request is logged above, and the details fall back to the Task in the tasks signal
+                let _ = task_resource.await;
+                details
+            })}
+        </Suspense>
+    }
+}
+
+#[component]
+fn TaskComments(task_id : TaskId, comments : Signal<Vec<Comment>>) -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let mutations = state.mutations;
+    let users = state.users;
+    let current_user = state.current_user;
+
+    // A signal to store the message of the comment being written
+    let (message, message_writer) = signal(String::new());
+
+    // A signal to store the id of the comment being edited, and a signal to store its edited message
+    let (editing_comment_id, editing_comment_id_writer) = signal(Option::<u32>::None);
+    let (edited_message, edited_message_writer) = signal(String::new());
+
+    view! {
+        <div>
+            <ul class="comment-header">
+                <li>Comments</li>
+            </ul>
+
+            <div class="comment-composer">
+                <textarea rows="3" placeholder="Write a comment" prop:value=move || message.get() on:input=move |e| {
+                    message_writer.set(event_target_value(&e));
+                }></textarea>
+                <div class="button-container">
+                    <button disabled=move || message.with(|message| message.trim().is_empty()) on:click=move |_| {
+
+                        // The comment is shown straight away with a local id, until the data source has given it an id
+                        mutations.create_comment(task_id, Comment {
+                            id: 0,
+                            user_id: current_user.get_untracked(),
+                            message: message.get_untracked().trim().to_string(),
+                            created_at: now(),
+                            edited_at: None
+                        });
+
+                        message_writer.set(String::new());
+                    }>
+                        "Post Comment"
+                    </button>
+                </div>
             </div>
 
-            <div>
-                <ul class="comment-header">
-                    <li>Comments</li>
-                </ul>
-                {
-                    task.comments.into_iter().map(|comment| {
-                        view! {
-                            <div class="comment">
-                                <div class="img">
-                                    <img src={format!("static/{}", comment.image)} />
-                                </div>
-                                <div class="body">
-                                    <div><h2>{comment.user}</h2></div>
-                                    <div>{comment.message}</div>
+            {move || {
+
+                // Comments are shown in the order they were posted
+                let mut comments = comments.get();
+                sort_comments_by_posted(&mut comments);
+
+                let current_time = now();
+
+                comments.into_iter().map(|comment| {
+
+                    let comment_id = comment.id;
+
+                    // Only the user who posted the comment can edit or delete it
+                    let is_own_comment = current_user.get() == comment.user_id;
+
+                    let message = comment.message.clone();
+
+                    // The name and avatar are found in the user directory from the comment's user id
+                    let (user_name, user_image) = users.map(|users| (users.name(comment.user_id), users.avatar(comment.user_id))).unwrap_or_default();
+
+                    view! {
+                        <div class="comment">
+                            <div class="img">
+                                <img src={user_image} />
+                            </div>
+                            <div class="body">
+                                <div class="comment-title">
+                                    <h2>{user_name}</h2>
+                                    // The full date is shown when the relative time is hovered
+                                    <time title={format_date_time(comment.created_at, &LocalTimeZone)}>{format_relative_time(comment.created_at, current_time, &LocalTimeZone)}</time>
+                                    {comment.edited_at.map(|edited_at| view! {
+                                        <time title={format_date_time(edited_at, &LocalTimeZone)}>"(edited)"</time>
+                                    })}
                                 </div>
+                                <Show 
+                                    when=move || { editing_comment_id.get() == Some(comment_id) }
+                                    fallback=move || view! { <div>{message.clone()}</div> }
+                                >
+                                    <textarea rows="3" prop:value=move || edited_message.get() on:input=move |e| {
+                                        edited_message_writer.set(event_target_value(&e));
+                                    }></textarea>
+                                    <div class="comment-actions">
+                                        <button disabled=move || edited_message.with(|message| message.trim().is_empty()) on:click=move |_| {
+                                            mutations.update_comment(task_id, comment_id, |comment| {
+                                                comment.message = edited_message.get_untracked().trim().to_string();
+                                                comment.edited_at = Some(now());
+                                            });
+                                            editing_comment_id_writer.set(None);
+                                        }>
+                                            "Save"
+                                        </button>
+                                        <button on:click=move |_| editing_comment_id_writer.set(None)>
+                                            "Cancel"
+                                        </button>
+                                    </div>
+                                </Show>
+                                <Show when=move || { is_own_comment && editing_comment_id.get() != Some(comment_id) }>
+                                    <ul class="comment-links">
+                                        <li on:click={
+                                            let message = comment.message.clone();
+                                            move |_| {
+                                                edited_message_writer.set(message.clone());
+                                                editing_comment_id_writer.set(Some(comment_id));
+                                            }
+                                        }>
+                                            "Edit"
+                                        </li>
+                                        <li on:click=move |_| {
+                                            mutations.delete_comment(task_id, comment_id);
+                                        }>
+                                            "Delete"
+                                        </li>
+                                    </ul>
+                                </Show>
                             </div>
-                        }
-                    }).collect_view()
-                }
-            </div>
+                        </div>
+                    }
+                }).collect_view()
+            }}
         </div>
     }
 }
 
 #[component]
-fn TaskForm(
-    task_form_writer : WriteSignal<bool>, 
-    counter : ReadSignal<u32>, 
-    counter_writer : WriteSignal<u32>, 
-    tasks_writer : WriteSignal<Vec<Task>>,) -> impl IntoView {
+fn TaskForm(edit_task : Option<Task>) -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let mutations = state.mutations;
+
+    // When a Task is being edited, the form is pre-filled with it. Otherwise the form starts with an empty Task.
+    let is_edit = edit_task.is_some();
 
-    let (task, task_writer) = signal(Task::default());
+    let (task, task_writer) = signal(edit_task.unwrap_or_else(|| {
+        // New Tasks are due tomorrow unless another due date is picked
+        Task { due_date: now().add_days(1).start_of_day(&LocalTimeZone), ..Task::default() }
+    }));
+
+    // Boolean signal to store if the user has confirmed that the Task can be due in the past
+    let (allow_past_due_date, allow_past_due_date_writer) = signal(false);
+
+    // The validation errors of the Task being entered. The Task can only be saved when there are no errors.
+    let errors = Memo::new(move |_| {
+        let rules = ValidationRules { allow_past_due_date: allow_past_due_date.get(), ..ValidationRules::default() };
+        task.with(|task| validate_task(task, &rules, start_of_today())).err().unwrap_or_default()
+    });
 
     view! {
         <div class="form-container">
             <div class="input-row">
-                <input type="text" placeholder="Task name" on:input = move |e| { 
+                <input type="text" placeholder="Task name" prop:value=move || task.with(|task| task.name.clone()) on:input = move |e| { 
                     task_writer.write().name = event_target_value(&e)
                 } />
+                <FieldErrors errors=errors field=TaskField::Name />
             </div>
 
             <div class="input-row">
-                <textarea rows="10" placeholder="Task description" on:input = move |e| { 
+                <textarea rows="10" placeholder="Task description" prop:value=move || task.with(|task| task.description.clone()) on:input = move |e| { 
                     task_writer.write().description = event_target_value(&e)
                 }></textarea>
+                <FieldErrors errors=errors field=TaskField::Description />
+            </div>
+
+            <div class="input-row picker-row">
+                <div class={move || format!("task-attribute {}", task.with(|task| task.priority.css_class()))}>
+                    <label>Priority</label>
+                    <TaskPrioritySelect 
+                        priority=Signal::derive(move || task.with(|task| task.priority.clone()))
+                        on_change=move |priority| task_writer.write().priority = priority
+                    />
+                </div>
+                <div class={move || format!("task-attribute {}", task.with(|task| task.status.css_class()))}>
+                    <label>Status</label>
+                    <TaskStatusSelect 
+                        status=Signal::derive(move || task.with(|task| task.status.clone()))
+                        on_change=move |status| task_writer.write().status = status
+                    />
+                </div>
             </div>
 
+            <DueDatePicker 
+                due_date=Signal::derive(move || task.with(|task| task.due_date))
+                on_change=move |due_date| task_writer.write().due_date = due_date
+            />
+            <FieldErrors errors=errors field=TaskField::DueDate />
+
+            // The user has to confirm a due date in the past, as it is most likely a mistake
+            <Show when=move || { allow_past_due_date.get() || errors.with(|errors| errors.contains(&TaskError::DueDateInPast)) }>
+                <label class="input-confirm">
+                    <input type="checkbox" prop:checked=move || allow_past_due_date.get() on:change=move |e| {
+                        allow_past_due_date_writer.set(event_target_checked(&e));
+                    } />
+                    "The task is due in the past"
+                </label>
+            </Show>
+
             <AssignUserList
-                task=task
-                task_writer=task_writer
+                assigned_to=Signal::derive(move || task.with(|task| task.assigned_to.clone()))
+                on_toggle=move |user_id| toggle_assigned_user(&mut task_writer.write().assigned_to, user_id)
             />
+            <FieldErrors errors=errors field=TaskField::AssignedTo />
 
             <div class="button-container">
-                <button on:click = move |_| {
+                <button disabled=move || errors.with(|errors| !errors.is_empty()) on:click = move |_| {
 
-                    counter_writer.set(counter.get() + 1);
+                    if errors.with_untracked(|errors| !errors.is_empty()) {
+                        return;
+                    }
 
-                    task_writer.write().id = counter.get();
-                    task_writer.write().due_date = Date::now();
+                    if is_edit {
+                        // Replace the Task that has the same id with the edited Task. The comments are kept, since they may have
+                        // changed while the form was open.
+                        let edited_task = task.get();
 
-                    tasks_writer.write().push(task.get());
+                        mutations.update_task(edited_task.id, "save", |existing_task| {
+                            *existing_task = Task { comments : std::mem::take(&mut existing_task.comments), ..edited_task };
+                        });
+                    } else {
+                        mutations.create_task(task.get());
+                    }
 
-                    task_form_writer.set(false);
+                    state.close_task_form();
                 }>
-                    "Create Task"
+                    { if is_edit { "Save Task" } else { "Create Task" } }
                 </button>
             </div>
         </div>
@@ -276,286 +1052,368 @@ fn TaskForm(
 }
 
 #[component]
-fn AssignedUsers(task : Task) -> impl IntoView {
+fn TrashList() -> impl IntoView {
+
+    let state = expect_context::<AppState>();
+    let trash = state.trash;
+    let trash_retention_days = state.trash_retention_days;
+
     view! {
-        <ul class="cell task-users-list">
-            {
-                task.assigned_to.into_iter().map(|u: User| {
-                    view! {
-                        <li><img src={format!("static/{}", u.image)} /></li>
-                    }
-                }).collect_view()
-            }
-        </ul>
+        <div class="form-container">
+            <div class="row">
+                <h2>Trash</h2>
+                <footer>
+                    <label class="trash-retention">
+                        "Purge deleted tasks after "
+                        <input type="number" min="1" prop:value=move || trash_retention_days.get() on:change=move |e| {
+                            if let Ok(days) = event_target_value(&e).parse::<u32>() {
+                                state.set_trash_retention_days(days);
+                            }
+                        } />
+                        " days"
+                    </label>
+                </footer>
+            </div>
+
+            <Show 
+                when=move || { trash.with(|trash| !trash.is_empty()) }
+                fallback=|| view! { <div class="row"><p>"The trash is empty."</p></div> }
+            >
+                <div class="trash-list">
+                    {move || {
+                        trash.get().into_iter().map(|trashed_task| {
+
+                            let task_id = trashed_task.task.id;
+
+                            // The date the Task will be purged automatically
+                            let purge_date = trashed_task.purge_date(trash_retention_days.get());
+
+                            view! {
+                                <div class="trash-item">
+                                    <div class="body">
+                                        <h3>{trashed_task.task.name}</h3>
+                                        <span>{format!("Deleted {}, purged on {}", format_date(trashed_task.deleted_at, &LocalTimeZone), format_date(purge_date, &LocalTimeZone))}</span>
+                                    </div>
+                                    <button on:click=move |_| {
+                                        state.mutations.restore_task_from_trash(task_id);
+                                    }>
+                                        "Restore"
+                                    </button>
+                                    <button class="button-danger" on:click=move |_| {
+                                        state.delete_task_forever(task_id);
+                                    }>
+                                        "Delete Forever"
+                                    </button>
+                                </div>
+                            }
+                        }).collect_view()
+                    }}
+                </div>
+
+                <div class="button-container">
+                    <button class="button-danger" on:click=move |_| {
+                        state.empty_trash();
+                    }>
+                        "Empty Trash"
+                    </button>
+                </div>
+            </Show>
+        </div>
     }
 }
 
+// Renders the error toasts of the changes that could not be saved. A toast is dismissed when it is clicked.
 #[component]
-fn AssignUserList(task : ReadSignal<Task>, task_writer : WriteSignal<Task>) -> impl IntoView {
-
-    let (users_to_assign, users_to_assign_writer) = signal(Vec::<AssignUser>::new());
+fn Toasts() -> impl IntoView {
 
-    for user in get_users(){
-        users_to_assign_writer.write().push(AssignUser { user: user, is_assigned: false });
-    }
+    let mutations = expect_context::<AppState>().mutations;
 
     view! {
-        <details class="user-list">
-            <summary>Assign Users</summary>
-            {move || {
+        <div class="toasts">
+            <For
+                each=move || mutations.toasts()
+                key=|toast| toast.id
+                children=move |toast| {
+                    // Each toast is dismissed after a while, or when it is clicked
+                    set_timeout(move || mutations.dismiss_toast(toast.id), TOAST_DURATION);
 
-                users_to_assign.get().into_iter().enumerate().map(|(index, assign_user)| {
+                    view! {
+                        <div class="toast" role="alert" on:click=move |_| mutations.dismiss_toast(toast.id)>
+                            {toast.message}
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
 
-                    let assign_user_clone = assign_user.clone();
+// Rendered in place of the TaskList while the Tasks are loading
+#[component]
+fn TaskListSkeleton() -> impl IntoView {
+    view! {
+        <div class="task-list">
+            {
+                (0..8).map(|_| view! {
+                    <div class="item skeleton-item">
+                        <span class="cell skeleton skeleton-id"></span>
+                        <span class="cell skeleton skeleton-name"></span>
+                        <span class="cell skeleton skeleton-users"></span>
+                        <span class="cell skeleton skeleton-date"></span>
+                    </div>
+                }).collect_view()
+            }
+        </div>
+    }
+}
 
-                    let mut item_css = "user-item";
+// Rendered in place of the TaskDetails while the selected Task is loading
+#[component]
+fn TaskDetailsSkeleton() -> impl IntoView {
+    view! {
+        <div class="form-container">
+            <div class="row">
+                <span class="skeleton skeleton-title"></span>
+                <span class="skeleton skeleton-attributes"></span>
+            </div>
+            <div class="row">
+                <span class="skeleton skeleton-line"></span>
+                <span class="skeleton skeleton-line"></span>
+                <span class="skeleton skeleton-line skeleton-line-short"></span>
+            </div>
+            <div class="row">
+                <span class="skeleton skeleton-users"></span>
+            </div>
+        </div>
+    }
+}
 
-                    if assign_user.is_assigned {
-                        item_css = "user-item user-item-selected";
+#[component]
+fn TaskPrioritySelect<F>(#[prop(into)] priority : Signal<TaskPriority>, on_change : F) -> impl IntoView
+where
+    F: Fn(TaskPriority) + 'static {
+    view! {
+        // Clicks are stopped so that changing the priority of a TaskList row does not also select the row
+        <select class="attribute-select" on:click=|e| e.stop_propagation() on:change=move |e| {
+            if let Ok(priority) = event_target_value(&e).parse::<TaskPriority>() {
+                on_change(priority);
+            }
+        }>
+            {
+                TaskPriority::ALL.into_iter().map(|option| {
+                    let key = option.key();
+                    let label = option.label();
+                    view! {
+                        <option value={key} prop:selected=move || priority.with(|priority| *priority == option)>{label}</option>
                     }
+                }).collect_view()
+            }
+        </select>
+    }
+}
 
+#[component]
+fn TaskStatusSelect<F>(#[prop(into)] status : Signal<TaskStatus>, on_change : F) -> impl IntoView
+where
+    F: Fn(TaskStatus) + 'static {
+    view! {
+        // Clicks are stopped so that changing the status of a TaskList row does not also select the row
+        <select class="attribute-select" on:click=|e| e.stop_propagation() on:change=move |e| {
+            if let Ok(status) = event_target_value(&e).parse::<TaskStatus>() {
+                on_change(status);
+            }
+        }>
+            {
+                TaskStatus::ALL.into_iter().map(|option| {
+                    let key = option.key();
+                    let label = option.label();
                     view! {
-                        <div class={item_css} on:click=move|_| {
-                            
-                            let mut updated_task = task.get();
+                        <option value={key} prop:selected=move || status.with(|status| *status == option)>{label}</option>
+                    }
+                }).collect_view()
+            }
+        </select>
+    }
+}
 
-                            if updated_task.assigned_to.contains(&assign_user.user) == false {
+// Renders the validation errors of a single input of the TaskForm
+#[component]
+fn FieldErrors(errors : Memo<Vec<TaskError>>, field : TaskField) -> impl IntoView {
+    move || {
+        errors.with(|errors| {
+            errors.iter().filter(|error| error.field() == field).map(|error| {
+                view! {
+                    <span class="input-error">{error.to_string()}</span>
+                }
+            }).collect_view()
+        })
+    }
+}
+
+#[component]
+fn DueDatePicker<F>(due_date : Signal<Timestamp>, on_change : F) -> impl IntoView
+where
+    F: Fn(Timestamp) + Copy + 'static {
 
-                                updated_task.assigned_to.push(User { name: assign_user.user.name.clone(), image: assign_user.user.image.clone() });
+    // Shortcuts shown next to the inputs, as a label and the number of days from today
+    let shortcuts = [("Today", 0), ("Tomorrow", 1), ("+1 week", 7), ("+2 weeks", 14), ("+1 month", 30)];
 
-                                task_writer.set(updated_task); 
+    view! {
+        <div class="input-row due-date-picker">
+            <label>Due Date</label>
+            <input type="date" prop:value=move || to_date_input_value(due_date.get(), &LocalTimeZone) on:change=move |e| {
+                // The time that has already been picked is kept when the date is changed
+                if let Some(timestamp) = from_date_time_input_values(&event_target_value(&e), &to_time_input_value(due_date.get_untracked(), &LocalTimeZone), &LocalTimeZone) {
+                    on_change(timestamp);
+                }
+            } />
+            <input type="time" prop:value=move || to_time_input_value(due_date.get(), &LocalTimeZone) on:change=move |e| {
+                // Clearing the time input makes the Task due at the start of the day
+                if let Some(timestamp) = from_date_time_input_values(&to_date_input_value(due_date.get_untracked(), &LocalTimeZone), &event_target_value(&e), &LocalTimeZone) {
+                    on_change(timestamp);
+                }
+            } />
+            <ul>
+                {
+                    shortcuts.into_iter().map(|(label, days)| {
+                        view! {
+                            <li on:click=move |_| {
+                                let date = to_date_input_value(now().add_days(days), &LocalTimeZone);
 
-                                if let Some(assign_user) = users_to_assign_writer.write().get_mut(index){
-                                    assign_user.is_assigned = true;
+                                if let Some(timestamp) = from_date_time_input_values(&date, &to_time_input_value(due_date.get_untracked(), &LocalTimeZone), &LocalTimeZone) {
+                                    on_change(timestamp);
                                 }
-                            }
-                        }>
-                            <img src={format!("static/{}", assign_user_clone.user.image)} />
-                            <span>{assign_user_clone.user.name}</span>
-                        </div>
-                    }
-                    
-                }).collect_view()
-            }}
-        </details>
+                            }>
+                                {label}
+                            </li>
+                        }
+                    }).collect_view()
+                }
+            </ul>
+        </div>
     }
 }
 
-fn main() {
-    mount_to_body(App);
-}
+#[component]
+fn AssignedUsers(assigned_to : Vec<UserId>) -> impl IntoView {
 
-// Function to simulate a fetch request and return a list of Users
-fn get_users() -> Vec<User> {
-    vec![
-        User {
-            name : "Derik".to_string(),
-            image : "person1.png".to_string(),
-        },
-        User {
-            name : "Fatima".to_string(),
-            image : "person2.png".to_string(),
-        },
-        User {
-            name : "John".to_string(),
-            image : "person3.png".to_string(),
-        },
-        User {
-            name : "Ilyana".to_string(),
-            image : "person4.png".to_string(),
-        },
-    ]
-}
-
-// Function to simulate a fetch request and return a list of Tasks
-fn get_tasks() -> Vec<Task> {
-    vec![
-        Task {
-            id : 1,
-            name : "Design login screen".to_string(),
-            description : "Create a responsive login screen with email and password fields, 'Forgot Password' link, and a login button. Include basic validation and error handling.".to_string(),
-            due_date : Date::new_0().add_days(5),
-            assigned_to : vec![ 
-                User{ name : "Derik".to_string(), image : "person1.png".to_string() }
-            ],
-            priority : TaskPriority::Medium(),
-            status : TaskStatus::Pending(),
-            comments : vec![]
-        },
-        Task {
-            id : 2,
-            name : "Write unit tests for task API".to_string(),
-            description : "Create unit tests for the task-related API endpoints, including task creation, status updates, and deletion. Use mock data and ensure edge cases are covered.".to_string(),
-            due_date : Date::new_0().add_days(25),
-            assigned_to : vec![ 
-                User{ name : "Derik".to_string(), image : "person1.png".to_string() },
-                User{ name : "Fatima".to_string(), image : "person2.png".to_string() },
-                User{ name : "John".to_string(), image : "person3.png".to_string() },
-            ],
-            priority : TaskPriority::High(),
-            status : TaskStatus::InProgress(),
-            comments : vec![]
-        },
-        Task {
-            id : 3,
-            name : "Implement product search with filters".to_string(),
-            description : "Develop a product search feature that allows users to search by name, category, and price range. Include filter options such as 'In Stock', 'On Sale', and 'Free Shipping'. 
-                Ensure the results update dynamically as filters are applied.".to_string(),
-            due_date : Date::new_0().add_days(45),
-            assigned_to : vec![ 
-                User{ name : "Derik".to_string(), image : "person1.png".to_string() },
-                User{ name : "Ilyana".to_string(), image : "person4.png".to_string() }
-            ],
-            priority : TaskPriority::Low(),
-            status : TaskStatus::New(),
-            comments : vec![]
-        },
-        Task {
-            id : 4,
-            name : "Integrate payment gateway".to_string(),
-            description : "Set up and integrate a payment gateway (e.g., Stripe or PayPal) to handle secure transactions during checkout. Implement payment validation, error handling, 
-                and confirmation messaging. Ensure the system can handle both test and live environments.".to_string(),
-            due_date : Date::new_0().add_days(10),
-            assigned_to : vec![ 
-                User{ name : "Derik".to_string(), image : "person1.png".to_string() },
-            ],
-            priority : TaskPriority::Medium(),
-            status : TaskStatus::Complete(),
-            comments : vec![]
-        },
-        Task {
-            id : 5,
-            name : "Create order history page".to_string(),
-            description : "Build a user-facing order history page that displays past purchases with order details, statuses, and tracking information. Include pagination and filtering by date or status.".to_string(),
-            due_date : Date::now(),
-            assigned_to : vec![ 
-                User{ name : "Ilyana".to_string(), image : "person4.png".to_string() },
-            ],
-            priority : TaskPriority::High(),
-            status : TaskStatus::Pending(),
-            comments : vec![]
-        },
-        Task {
-            id : 6,
-            name : "Implement product review system".to_string(),
-            description : "Allow users to leave reviews and ratings on products. Design the UI for submitting and displaying reviews, and create backend endpoints to store and fetch review data. 
-                Include moderation capabilities to filter inappropriate content.".to_string(),
-            due_date : Date::new_0().add_days(12),
-            assigned_to : vec![ 
-                User{ name : "John".to_string(), image : "person3.png".to_string() },
-                User{ name : "Ilyana".to_string(), image : "person4.png".to_string() }
-            ],
-            priority : TaskPriority::Low(),
-            status : TaskStatus::InProgress(),
-            comments : vec![
-                Comment{
-                    user : "John".to_string(),
-                    message : "Should reviews be visible to everyone immediately, or only after moderation?".to_string(),
-                    image: "person3.png".to_string()
-                },Comment{
-                    user : "Ilyana".to_string(),
-                    message : "Good question, I'll get back to you".to_string(),
-                    image: "person4.png".to_string()
-                },
-                Comment{
-                    user : "John".to_string(),
-                    message : "Ok, thanks, I'll wait for your reply".to_string(),
-                    image: "person3.png".to_string()
-                }
-            ]
-        },
-        Task {
-            id : 7,
-            name : "Add wishlist functionality".to_string(),
-            description : "Enable users to add products to a personal wishlist for future reference. Implement the UI for adding/removing items and a wishlist page to view saved products. 
-                Ensure the wishlist is saved per user and persists across sessions.".to_string(),
-            due_date : Date::new_0().add_days(45),
-            assigned_to : vec![ 
-                User{ name : "Derik".to_string(), image : "person2.png".to_string() }
-            ],
-            priority : TaskPriority::High(),
-            status : TaskStatus::InProgress(),
-            comments : vec![]
-        }
-    ]
+    let users = expect_context::<AppState>().users;
+
+    view! {
+        <ul class="cell task-users-list">
+            {move || {
+                users.map(|users| {
+                    assigned_to.iter().map(|user_id| {
+                        view! {
+                            <li><img src={users.avatar(*user_id)} title={users.name(*user_id)} /></li>
+                        }
+                    }).collect_view()
+                })
+            }}
+        </ul>
+    }
 }
 
-fn get_task(task_id : u32) -> Task {
+// Renders the users assigned to a Task with buttons to unassign them, and a list of all users where clicking a user
+// assigns or unassigns them. The component is used by the TaskForm and to change the users assigned to an existing Task.
+#[component]
+fn AssignUserList<F>(assigned_to : Signal<Vec<UserId>>, on_toggle : F) -> impl IntoView
+where
+    F: Fn(UserId) + Copy + Send + Sync + 'static {
 
-    let tasks = get_tasks();
+    let users = expect_context::<AppState>().users;
 
-    let task = tasks.iter().find(|task| {
-        task.id == task_id
-    }).unwrap().clone();
+    // The users are marked as assigned from the ids of the users already assigned to the Task
+    let users_to_assign = Memo::new(move |_| {
+        users.map(|users| {
+            assigned_to.with(|assigned_to| {
+                users.iter().map(|user| AssignUser { user: user.clone(), is_assigned: assigned_to.contains(&user.id) }).collect::<Vec<_>>()
+            })
+        }).unwrap_or_default()
+    });
 
-    task
-}
+    view! {
+        // The users are rendered once the users resource has loaded
+        <Suspense fallback=|| view! { <div class="skeleton skeleton-users"></div> }>
+        {move || Suspend::new(async move {
+        users.await;
 
-fn format_date(timestamp_ms : f64) -> String{
+        view! {
+        <div class="assign-users">
+            <ul class="assigned-users">
+                {move || {
+                    assigned_to.get().into_iter().map(|user_id| {
 
-    let months = [
-        "Jan", "Feb", "Mar", "Apr", "May", "Jun",
-        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
-    ];
+                        let (name, image) = users.map(|users| (users.name(user_id), users.avatar(user_id))).unwrap_or_default();
 
-    let date = Date::new(&timestamp_ms.into());
+                        view! {
+                            <li>
+                                <img src={image} />
+                                <span>{name}</span>
+                                <button title="Unassign user" on:click=move |_| on_toggle(user_id)>
+                                    "\u{2715}"
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()
+                }}
+            </ul>
+            <details class="user-list">
+                <summary>Assign Users</summary>
+                {move || {
 
-    let year = date.get_full_year();
-    let month = date.get_month();
-    let day = date.get_date();
+                    users_to_assign.get().into_iter().map(|assign_user| {
 
-    format!("{} {} {}", day, months[month as usize], year)
-}
+                        let assign_user_clone = assign_user.clone();
+                        let image = users.map(|users| users.avatar(assign_user.user.id)).unwrap_or_default();
 
-#[derive(Clone, Default)]
-struct Task {
-    id : u32,
-    name : String,
-    description : String,
-    assigned_to : Vec<User>,
-    due_date : f64,
-    priority : TaskPriority,
-    status : TaskStatus,
-    comments : Vec<Comment>
-}
-#[derive(Clone)]
-struct Comment {
-    user : String,
-    message : String,
-    image : String
-}
+                        let mut item_css = "user-item";
 
-#[derive(Clone)]
-enum TaskStatus {
-    New(),
-    Pending(),
-    InProgress(),
-    Complete(),
-}
+                        if assign_user.is_assigned {
+                            item_css = "user-item user-item-selected";
+                        }
 
-impl Default for TaskStatus {
-    fn default() -> Self {
-        Self::New()
+                        view! {
+                            <div class={item_css} on:click=move|_| {
+                                // Clicking an assigned user unassigns them
+                                on_toggle(assign_user.user.id);
+                            }>
+                                <img src={image} />
+                                <span>{assign_user_clone.user.name}</span>
+                            </div>
+                        }
+                        
+                    }).collect_view()
+                }}
+            </details>
+        </div>
+        }
+        })}
+        </Suspense>
     }
 }
 
-#[derive(Clone)]
-enum TaskPriority {
-    Low(),
-    Medium(),
-    High()
+fn main() {
+    mount_to_body(App);
 }
 
-impl Default for TaskPriority {
-    fn default() -> Self {
-        Self::Low()
+// The time zone of the browser. Dates are shown and picked in the user's local time.
+struct LocalTimeZone;
+
+impl TimeZone for LocalTimeZone {
+    fn utc_offset_minutes(&self, timestamp : Timestamp) -> i32 {
+        // getTimezoneOffset is the number of minutes local time is behind UTC
+        -(Date::new(&timestamp.0.into()).get_timezone_offset() as i32)
     }
 }
 
-#[derive(Clone, Default, PartialEq)]
-struct User {
-    name : String,
-    image : String
+fn now() -> Timestamp {
+    Timestamp(Date::now())
+}
+
+// Returns the timestamp of midnight today, in local time
+fn start_of_today() -> Timestamp {
+    now().start_of_day(&LocalTimeZone)
 }
 
 #[derive(Clone, Default, PartialEq)]
@@ -563,15 +1421,3 @@ struct AssignUser {
     user : User,
     is_assigned : bool
 }
-
-pub trait AddDaysExt {
-    fn add_days(&self, days : i32) -> f64;
-}
-
-impl AddDaysExt for Date {
-    fn add_days(&self, days : i32) -> f64 {
-        let ms_in_day = 86_400_000.0;
-        let new_time = self.get_time() + (days as f64 * ms_in_day);
-        new_time
-    }
-}
\ No newline at end of file
@@ -0,0 +1,588 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::Duration;
+
+use leptos::prelude::*;
+use todo_core::model::{Comment, Task, TaskId, TrashedTask};
+use todo_core::tasks::{insert_task, take_task};
+
+use crate::data::{AppDataSource, DataError, DataSource};
+use crate::now;
+
+// How long an error toast is shown before it is dismissed by the Toasts component
+pub const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+// A change that has been applied to the Tasks and is waiting for the data source to confirm it
+#[derive(Clone, PartialEq)]
+struct PendingMutation {
+    id : u64,
+    task_id : TaskId
+}
+
+// A message shown when a change could not be saved
+#[derive(Clone, PartialEq)]
+pub struct Toast {
+    pub id : u64,
+    pub message : String
+}
+
+// Applies changes to the Tasks optimistically. A change is applied to the tasks signal straight away and then sent to the
+// data source, and the Task is pending until the data source responds. If the data source rejects the change, the change
+// is rolled back and an error toast is shown. The data source is a type parameter so that the mutations can be tested natively.
+#[derive(Clone, Copy)]
+pub(crate) struct Mutations<S = AppDataSource> {
+    pub source : S,
+    tasks_writer : WriteSignal<Vec<Task>>,
+    trash_writer : WriteSignal<Vec<TrashedTask>>,
+    pending : RwSignal<Vec<PendingMutation>>,
+    // The id of the last change sent for each Task, to tell whether a Task requested from the data source includes it
+    last_sent : StoredValue<HashMap<TaskId, u64>>,
+    toasts : RwSignal<Vec<Toast>>,
+    next_id : StoredValue<u64>,
+    next_local_task_id : StoredValue<u32>,
+    next_local_comment_id : StoredValue<u32>
+}
+
+impl<S : DataSource + Copy + 'static> Mutations<S> {
+    pub fn new(source : S, tasks_writer : WriteSignal<Vec<Task>>, trash_writer : WriteSignal<Vec<TrashedTask>>) -> Self {
+        Self {
+            source,
+            tasks_writer,
+            trash_writer,
+            pending : RwSignal::new(Vec::new()),
+            last_sent : StoredValue::new(HashMap::new()),
+            toasts : RwSignal::new(Vec::new()),
+            next_id : StoredValue::new(0),
+            next_local_task_id : StoredValue::new(u32::MAX),
+            next_local_comment_id : StoredValue::new(u32::MAX)
+        }
+    }
+
+    // Marks the point a request for a Task is started. The mark is given to apply_requested_task with the requested Task.
+    pub fn request_mark(&self) -> u64 {
+        self.next_id.get_value()
+    }
+
+    // Replaces the Task in the list with the Task requested from the data source, without sending it back to the data source.
+    // The requested Task is dropped if a change to the Task is pending, or was sent after the request was started, since the
+    // requested Task may not include the change. Returns true if the Task was replaced.
+    pub fn apply_requested_task(&self, requested_task : Task, request_mark : u64) -> bool {
+        let task_id = requested_task.id;
+        let is_pending = untrack(|| self.is_pending(task_id));
+        let changed_since = self.last_sent.with_value(|last_sent| last_sent.get(&task_id).is_some_and(|mutation_id| *mutation_id > request_mark));
+
+        if is_pending || changed_since {
+            return false;
+        }
+
+        let mut replaced = false;
+
+        update_local_task(self.tasks_writer, task_id, |task| {
+            if *task != requested_task {
+                *task = requested_task;
+                replaced = true;
+            }
+        });
+
+        replaced
+    }
+
+    // True while a change to the Task is waiting for the data source
+    pub fn is_pending(&self, task_id : TaskId) -> bool {
+        self.pending.with(|pending| pending.iter().any(|mutation| mutation.task_id == task_id))
+    }
+
+    // The Tasks with changes that are waiting for the data source
+    pub fn pending_task_ids(&self) -> HashSet<TaskId> {
+        self.pending.with(|pending| pending.iter().map(|mutation| mutation.task_id).collect())
+    }
+
+    pub fn toasts(&self) -> Vec<Toast> {
+        self.toasts.get()
+    }
+
+    pub fn dismiss_toast(&self, toast_id : u64) {
+        self.toasts.update(|toasts| toasts.retain(|toast| toast.id != toast_id));
+    }
+
+    // Applies the update to the Task, and puts the Task back to how it was if the data source rejects the updated Task.
+    // The action describes the change in the error toast, e.g. "change the status of". The comments of the Task are
+    // changed with the comment mutations, since the data source does not update them with the Task.
+    pub fn update_task(&self, task_id : TaskId, action : &'static str, update : impl FnOnce(&mut Task)) {
+
+        let mut previous_task = None;
+        let mut updated_task = None;
+
+        update_local_task(self.tasks_writer, task_id, |task| {
+            previous_task = Some(task.clone());
+            update(task);
+            updated_task = Some(task.clone());
+        });
+
+        let (Some(previous_task), Some(updated_task)) = (previous_task, updated_task) else {
+            return;
+        };
+
+        if previous_task == updated_task {
+            return;
+        }
+
+        let source = self.source;
+        let tasks_writer = self.tasks_writer;
+        let sent_task = updated_task.clone();
+
+        self.send(
+            task_id,
+            action,
+            async move { source.update_task(sent_task).await },
+            |_| (),
+            // The Task is only put back if it has not been changed again since, so that a later change is not undone
+            move || update_local_task(tasks_writer, task_id, |task| {
+                if *task == updated_task {
+                    *task = previous_task;
+                }
+            })
+        );
+    }
+
+    // Adds the Task to the list with a local id, and replaces it with the created Task once the data source has given it an id.
+    // The Task is removed again if the data source rejects it.
+    pub fn create_task(&self, task : Task) {
+
+        let local_id = self.next_local_task_id();
+        let source = self.source;
+        let tasks_writer = self.tasks_writer;
+
+        insert_task(&mut tasks_writer.write(), Task { id : local_id, ..task.clone() });
+
+        self.send(
+            local_id,
+            "create",
+            async move { source.create_task(Task { id : TaskId(0), ..task }).await },
+            move |created_task| {
+                let mut tasks = tasks_writer.write();
+
+                if take_task(&mut tasks, local_id).is_some() {
+                    insert_task(&mut tasks, created_task);
+                }
+            },
+            move || tasks_writer.write().retain(|task| task.id != local_id)
+        );
+    }
+
+    // Adds the comment to the Task with a local id, and replaces it with the created comment once the data source has given
+    // it an id. The comment is removed again if the data source rejects it.
+    pub fn create_comment(&self, task_id : TaskId, comment : Comment) {
+
+        let local_id = self.next_local_comment_id();
+        let source = self.source;
+        let tasks_writer = self.tasks_writer;
+
+        update_local_task(tasks_writer, task_id, |task| task.comments.push(Comment { id : local_id, ..comment.clone() }));
+
+        self.send(
+            task_id,
+            "post the comment on",
+            async move { source.create_comment(task_id, comment).await },
+            move |created_comment| update_local_task(tasks_writer, task_id, |task| {
+                if let Some(comment) = task.comments.iter_mut().find(|comment| comment.id == local_id) {
+                    *comment = created_comment;
+                }
+            }),
+            move || update_local_task(tasks_writer, task_id, |task| task.comments.retain(|comment| comment.id != local_id))
+        );
+    }
+
+    // Applies the update to the comment, and puts the comment back to how it was if the data source rejects it
+    pub fn update_comment(&self, task_id : TaskId, comment_id : u32, update : impl FnOnce(&mut Comment)) {
+
+        let mut previous_comment = None;
+        let mut updated_comment = None;
+
+        update_local_comment(self.tasks_writer, task_id, comment_id, |comment| {
+            previous_comment = Some(comment.clone());
+            update(comment);
+            updated_comment = Some(comment.clone());
+        });
+
+        let (Some(previous_comment), Some(updated_comment)) = (previous_comment, updated_comment) else {
+            return;
+        };
+
+        if previous_comment == updated_comment {
+            return;
+        }
+
+        let source = self.source;
+        let tasks_writer = self.tasks_writer;
+        let sent_comment = updated_comment.clone();
+
+        self.send(
+            task_id,
+            "edit the comment on",
+            async move { source.update_comment(task_id, sent_comment).await },
+            move |saved_comment| update_local_comment(tasks_writer, task_id, comment_id, |comment| *comment = saved_comment),
+            move || update_local_comment(tasks_writer, task_id, comment_id, |comment| {
+                if *comment == updated_comment {
+                    *comment = previous_comment;
+                }
+            })
+        );
+    }
+
+    // Removes the comment from the Task, and adds it back if the data source rejects the delete
+    pub fn delete_comment(&self, task_id : TaskId, comment_id : u32) {
+
+        let mut deleted_comment = None;
+
+        update_local_task(self.tasks_writer, task_id, |task| {
+            if let Some(index) = task.comments.iter().position(|comment| comment.id == comment_id) {
+                deleted_comment = Some(task.comments.remove(index));
+            }
+        });
+
+        let Some(deleted_comment) = deleted_comment else {
+            return;
+        };
+
+        let source = self.source;
+        let tasks_writer = self.tasks_writer;
+
+        self.send(
+            task_id,
+            "delete the comment on",
+            async move { source.delete_comment(task_id, comment_id).await },
+            |_| (),
+            move || update_local_task(tasks_writer, task_id, |task| {
+                let position = task.comments.partition_point(|comment| comment.id < comment_id);
+                task.comments.insert(position, deleted_comment);
+            })
+        );
+    }
+
+    // Removes the Task from the list of Tasks and moves it into the trash. The trash is kept by the app, so the Task is deleted
+    // from the data source. The Task is moved back into the list if the data source rejects the delete.
+    pub fn move_task_to_trash(&self, task_id : TaskId) {
+
+        let tasks_writer = self.tasks_writer;
+        let trash_writer = self.trash_writer;
+
+        let Some(task) = take_task(&mut tasks_writer.write(), task_id) else {
+            return;
+        };
+
+        trash_writer.write().push(TrashedTask { task, deleted_at: now() });
+
+        let source = self.source;
+
+        self.send(
+            task_id,
+            "delete",
+            async move { source.delete_task(task_id).await },
+            |_| (),
+            move || {
+                let mut trash = trash_writer.write();
+
+                if let Some(index) = trash.iter().position(|trashed_task| trashed_task.task.id == task_id) {
+                    insert_task(&mut tasks_writer.write(), trash.remove(index).task);
+                }
+            }
+        );
+    }
+
+    // Removes the Task from the trash and puts it back into the list of Tasks, in the position given by its id.
+    // The Task was deleted from the data source when it was moved to the trash, so it is created again. If its id has
+    // been given to another Task since, the data source rejects it with a conflict, and it is created with a new id.
+    // The Task is moved back into the trash if the data source rejects it otherwise.
+    pub fn restore_task_from_trash(&self, task_id : TaskId) {
+
+        let tasks_writer = self.tasks_writer;
+        let trash_writer = self.trash_writer;
+
+        let trashed_task = {
+            let mut trash = trash_writer.write();
+
+            match trash.iter().position(|trashed_task| trashed_task.task.id == task_id) {
+                Some(index) => trash.remove(index),
+                None => return
+            }
+        };
+
+        insert_task(&mut tasks_writer.write(), trashed_task.task.clone());
+
+        let source = self.source;
+        let task = trashed_task.task.clone();
+
+        self.send(
+            task_id,
+            "restore",
+            async move {
+                match source.create_task(task.clone()).await {
+                    Err(error) if error.conflict => source.create_task(Task { id : TaskId(0), ..task }).await,
+                    result => result
+                }
+            },
+            move |created_task| {
+                if created_task.id != task_id {
+                    let mut tasks = tasks_writer.write();
+
+                    if take_task(&mut tasks, task_id).is_some() {
+                        insert_task(&mut tasks, created_task);
+                    }
+                }
+            },
+            move || {
+                if take_task(&mut tasks_writer.write(), task_id).is_some() {
+                    trash_writer.write().push(trashed_task);
+                }
+            }
+        );
+    }
+
+    // Sends the request to the data source and tracks the Task as pending until it responds
+    fn send<T : 'static>(
+        &self,
+        task_id : TaskId,
+        action : &'static str,
+        request : impl Future<Output = Result<T, DataError>> + 'static,
+        on_success : impl FnOnce(T) + 'static,
+        rollback : impl FnOnce() + 'static) {
+
+        let mutation_id = self.next_id();
+
+        self.pending.update(|pending| pending.push(PendingMutation { id: mutation_id, task_id }));
+        self.last_sent.update_value(|last_sent| { last_sent.insert(task_id, mutation_id); });
+
+        let mutations = *self;
+
+        leptos::task::spawn_local(async move {
+            let result = request.await;
+
+            mutations.pending.update(|pending| pending.retain(|mutation| mutation.id != mutation_id));
+
+            match result {
+                Ok(value) => on_success(value),
+                Err(error) => {
+                    rollback();
+                    mutations.show_toast(format!("Could not {} {}. {}", action, task_id, error));
+                }
+            }
+        });
+    }
+
+    fn show_toast(&self, message : String) {
+
+        let toast_id = self.next_id();
+
+        self.toasts.update(|toasts| toasts.push(Toast { id: toast_id, message }));
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.update_value(|id| *id += 1);
+        self.next_id.get_value()
+    }
+
+    // Returns an id for a Task that is waiting for the data source to give it an id. Local ids count down from the
+    // largest id, so they do not collide with the ids given by the data source.
+    fn next_local_task_id(&self) -> TaskId {
+        self.next_local_task_id.update_value(|id| *id -= 1);
+        TaskId(self.next_local_task_id.get_value() + 1)
+    }
+
+    // Returns an id for a comment that is waiting for the data source to give it an id, counting down from the largest id
+    // like the local Task ids
+    fn next_local_comment_id(&self) -> u32 {
+        self.next_local_comment_id.update_value(|id| *id -= 1);
+        self.next_local_comment_id.get_value() + 1
+    }
+}
+
+// Finds the Task with the given id and applies the update to it, without sending the change to the data source
+pub fn update_local_task(tasks_writer : WriteSignal<Vec<Task>>, task_id : TaskId, update : impl FnOnce(&mut Task)) {
+    if let Some(task) = tasks_writer.write().iter_mut().find(|task| task.id == task_id) {
+        update(task);
+    }
+}
+
+// Finds the comment with the given id on the Task and applies the update to it, without sending the change to the data source
+fn update_local_comment(tasks_writer : WriteSignal<Vec<Task>>, task_id : TaskId, comment_id : u32, update : impl FnOnce(&mut Comment)) {
+    update_local_task(tasks_writer, task_id, |task| {
+        if let Some(comment) = task.comments.iter_mut().find(|comment| comment.id == comment_id) {
+            update(comment);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use any_spawner::Executor;
+    use todo_core::model::{User, UserId};
+    use todo_core::time::Timestamp;
+
+    use super::*;
+
+    // A data source that responds straight away, accepting every request or rejecting every request. The responses are
+    // handled when the spawned requests are run with Executor::poll_local.
+    #[derive(Clone, Copy)]
+    struct TestDataSource {
+        rejects : bool,
+        next_comment_id : StoredValue<u32>
+    }
+
+    impl TestDataSource {
+        fn respond<T>(&self, value : T) -> Result<T, DataError> {
+            if self.rejects {
+                Err(DataError { message : "Rejected".to_string(), conflict : false })
+            } else {
+                Ok(value)
+            }
+        }
+    }
+
+    impl DataSource for TestDataSource {
+        async fn list_tasks(&self) -> Result<Vec<Task>, DataError> {
+            self.respond(Vec::new())
+        }
+
+        async fn get_task(&self, _task_id : TaskId) -> Result<Option<Task>, DataError> {
+            self.respond(None)
+        }
+
+        async fn create_task(&self, task : Task) -> Result<Task, DataError> {
+            self.respond(Task { id : TaskId(100), ..task })
+        }
+
+        async fn update_task(&self, task : Task) -> Result<Task, DataError> {
+            self.respond(task)
+        }
+
+        async fn delete_task(&self, _task_id : TaskId) -> Result<(), DataError> {
+            self.respond(())
+        }
+
+        async fn list_users(&self) -> Result<Vec<User>, DataError> {
+            self.respond(Vec::new())
+        }
+
+        async fn create_comment(&self, _task_id : TaskId, comment : Comment) -> Result<Comment, DataError> {
+            self.next_comment_id.update_value(|id| *id += 1);
+            self.respond(Comment { id : self.next_comment_id.get_value(), ..comment })
+        }
+
+        async fn update_comment(&self, _task_id : TaskId, comment : Comment) -> Result<Comment, DataError> {
+            self.respond(comment)
+        }
+
+        async fn delete_comment(&self, _task_id : TaskId, _comment_id : u32) -> Result<(), DataError> {
+            self.respond(())
+        }
+    }
+
+    struct Fixture {
+        _owner : Owner,
+        mutations : Mutations<TestDataSource>,
+        tasks : ReadSignal<Vec<Task>>
+    }
+
+    fn fixture(rejects : bool) -> Fixture {
+        let _ = Executor::init_futures_executor();
+
+        let owner = Owner::new();
+        owner.set();
+
+        let (tasks, tasks_writer) = signal(vec![task(1, "First"), task(2, "Second")]);
+        let (_, trash_writer) = signal(Vec::new());
+
+        Fixture { _owner : owner, mutations : Mutations::new(TestDataSource { rejects, next_comment_id : StoredValue::new(0) }, tasks_writer, trash_writer), tasks }
+    }
+
+    fn task(id : u32, name : &str) -> Task {
+        Task { id : TaskId(id), name : name.to_string(), ..Task::default() }
+    }
+
+    fn task_names(tasks : ReadSignal<Vec<Task>>) -> Vec<(u32, String)> {
+        tasks.get_untracked().into_iter().map(|task| (task.id.0, task.name)).collect()
+    }
+
+    #[test]
+    fn applies_changes_straight_away_and_keeps_them_once_saved() {
+        let Fixture { _owner, mutations, tasks } = fixture(false);
+
+        mutations.update_task(TaskId(1), "rename", |task| task.name = "Renamed".to_string());
+
+        assert_eq!(task_names(tasks), vec![(1, "Renamed".to_string()), (2, "Second".to_string())]);
+        assert!(mutations.is_pending(TaskId(1)));
+
+        Executor::poll_local();
+
+        assert_eq!(task_names(tasks), vec![(1, "Renamed".to_string()), (2, "Second".to_string())]);
+        assert!(!mutations.is_pending(TaskId(1)));
+        assert!(mutations.toasts().is_empty());
+    }
+
+    #[test]
+    fn rolls_back_rejected_changes() {
+        let Fixture { _owner, mutations, tasks } = fixture(true);
+
+        mutations.update_task(TaskId(1), "rename", |task| task.name = "Renamed".to_string());
+        Executor::poll_local();
+
+        assert_eq!(task_names(tasks), vec![(1, "First".to_string()), (2, "Second".to_string())]);
+        assert_eq!(mutations.toasts().len(), 1);
+    }
+
+    #[test]
+    fn replaces_the_local_id_of_a_created_task() {
+        let Fixture { _owner, mutations, tasks } = fixture(false);
+
+        mutations.create_task(task(0, "New"));
+
+        assert_eq!(task_names(tasks)[2], (u32::MAX, "New".to_string()));
+
+        Executor::poll_local();
+
+        assert_eq!(task_names(tasks), vec![(1, "First".to_string()), (2, "Second".to_string()), (100, "New".to_string())]);
+    }
+
+    #[test]
+    fn replaces_the_local_ids_of_created_comments() {
+        let Fixture { _owner, mutations, tasks } = fixture(false);
+        let comment = |message : &str| Comment { id : 0, user_id : UserId(1), message : message.to_string(), created_at : Timestamp(0.0), edited_at : None };
+        let comments = || tasks.get_untracked()[0].comments.iter().map(|comment| (comment.id, comment.message.clone())).collect::<Vec<_>>();
+
+        // Both comments are posted before the data source has given either of them an id
+        mutations.create_comment(TaskId(1), comment("A"));
+        mutations.create_comment(TaskId(1), comment("B"));
+
+        assert_eq!(comments(), vec![(u32::MAX, "A".to_string()), (u32::MAX - 1, "B".to_string())]);
+
+        Executor::poll_local();
+
+        assert_eq!(comments(), vec![(1, "A".to_string()), (2, "B".to_string())]);
+    }
+
+    #[test]
+    fn applies_a_requested_task_only_if_the_task_has_not_changed_since() {
+        let Fixture { _owner, mutations, tasks } = fixture(false);
+
+        // Nothing has changed since the request was started
+        let request_mark = mutations.request_mark();
+        assert!(mutations.apply_requested_task(task(2, "Requested"), request_mark));
+        assert_eq!(task_names(tasks)[1], (2, "Requested".to_string()));
+
+        // The Task is renamed while the request is pending, so the requested Task does not include the new name,
+        // whether the rename is still pending or has been saved
+        let request_mark = mutations.request_mark();
+        mutations.update_task(TaskId(1), "rename", |task| task.name = "Renamed".to_string());
+
+        assert!(!mutations.apply_requested_task(task(1, "First"), request_mark));
+        Executor::poll_local();
+        assert!(!mutations.apply_requested_task(task(1, "First"), request_mark));
+        assert_eq!(task_names(tasks)[0], (1, "Renamed".to_string()));
+
+        // A request started after the rename was saved includes it
+        let request_mark = mutations.request_mark();
+        assert!(mutations.apply_requested_task(task(1, "Renamed elsewhere"), request_mark));
+        assert_eq!(task_names(tasks)[0], (1, "Renamed elsewhere".to_string()));
+    }
+}
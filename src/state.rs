@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use leptos::logging;
+use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_navigate};
+use leptos_router::NavigateOptions;
+use todo_core::filter::{toggle, DueRange, TaskFilter};
+use todo_core::model::{Task, TaskId, TaskPriority, TaskStatus, TrashedTask, UserDirectory, UserId, DEFAULT_TRASH_RETENTION_DAYS};
+use todo_core::panel::Panel;
+use todo_core::search::{SearchIndex, SearchQuery};
+use todo_core::sort::{SortColumn, TaskSort};
+use todo_core::tasks::{has_expired_tasks, purge_expired_tasks};
+use todo_core::views::{format_query, parse_query, save_view, SavedView, TaskView};
+
+use crate::data::{AppDataSource, DataError, DataSource};
+use crate::mutations::Mutations;
+use crate::{now, storage, LocalTimeZone};
+
+// How often the trash is checked for expired Tasks while the app stays open
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Navigates to a URL. The function returned by use_navigate is kept in the state, since the actions are called from
+// event handlers where the Router can not be found.
+type Navigate = Box<dyn Fn(&str, NavigateOptions)>;
+
+// The state shared by the components of the app. The state is provided as context by the TaskApp component, and components
+// get it with expect_context instead of having the signals passed down as props. The signals are changed through the
+// actions below, so that the rules of the UI, such as which panel is rendered, are kept in one place.
+#[derive(Clone, Copy)]
+pub(crate) struct AppState {
+    pub tasks : ReadSignal<Vec<Task>>,
+    pub tasks_resource : LocalResource<Result<Vec<Task>, DataError>>,
+    pub tasks_loaded : ReadSignal<bool>,
+    pub users : LocalResource<UserDirectory>,
+    pub current_user : ReadSignal<UserId>,
+    current_user_writer : WriteSignal<UserId>,
+    pub panel : Memo<Panel>,
+    navigate : StoredValue<Navigate, LocalStorage>,
+    pub sort : ReadSignal<TaskSort>,
+    sort_writer : WriteSignal<TaskSort>,
+    pub filter : ReadSignal<TaskFilter>,
+    filter_writer : WriteSignal<TaskFilter>,
+    pub search : ReadSignal<String>,
+    search_writer : WriteSignal<String>,
+    pub search_query : Memo<SearchQuery>,
+    search_index : StoredValue<SearchIndex>,
+    pub saved_views : ReadSignal<Vec<SavedView>>,
+    saved_views_writer : WriteSignal<Vec<SavedView>>,
+    pub trash : ReadSignal<Vec<TrashedTask>>,
+    trash_writer : WriteSignal<Vec<TrashedTask>>,
+    pub trash_retention_days : ReadSignal<u32>,
+    trash_retention_days_writer : WriteSignal<u32>,
+    pub mutations : Mutations
+}
+
+impl AppState {
+    // Creates the state of the app. The state must be created inside the Router, since the panel follows the URL.
+    pub fn new(source : AppDataSource) -> Self {
+
+        // A resource to request the directory of Users that Tasks and comments reference by id.
+        // If the request fails the directory is empty, and users are shown as unknown.
+        let users = LocalResource::new(move || async move {
+            match source.list_users().await {
+                Ok(users) => UserDirectory::new(users),
+                Err(error) => {
+                    logging::error!("Failed to load users: {}", error);
+                    UserDirectory::default()
+                }
+            }
+        });
+
+        // A signal to hold the Tasks that have been moved to the trash. Trashed Tasks are persisted in localStorage
+        // so that they can be restored after the app is reloaded. The stored trash is loaded once the users have loaded,
+        // since trashed Tasks stored by earlier versions of the app reference users by name, and it is not saved until then.
+        let (trash, trash_writer) = signal(Vec::<TrashedTask>::new());
+        let (trash_loaded, trash_loaded_writer) = signal(false);
+
+        Effect::new(move |_| {
+            users.map(|users| {
+                if !trash_loaded.get_untracked() {
+                    let stored_trash = storage::load_trash(storage::TRASH_KEY, users);
+
+                    // Tasks moved to the trash before the stored trash was loaded are kept after the stored Tasks
+                    trash_writer.update(|trash| {
+                        trash.splice(0..0, stored_trash);
+                    });
+                    trash_loaded_writer.set(true);
+                }
+            });
+        });
+
+        Effect::new(move |_| {
+            if trash_loaded.get() {
+                trash.with(|trash| storage::save(storage::TRASH_KEY, trash));
+            }
+        });
+
+        // A resource to request the list of Tasks. The TaskList renders a loading state until the request has completed.
+        let tasks_resource = LocalResource::new(move || async move { source.list_tasks().await });
+
+        // A Signal to hold a vector of Tasks. The signal is filled once the tasks_resource has loaded.
+        // Until then, the panel of a Task is not rendered, so that a link to a Task is not shown as not found while it loads.
+        let (tasks, tasks_writer) = signal(Vec::<Task>::new());
+        let (tasks_loaded, tasks_loaded_writer) = signal(false);
+
+        Effect::new(move |_| {
+            if let Some(Ok(loaded_tasks)) = tasks_resource.get() {
+                tasks_writer.set(loaded_tasks);
+                tasks_loaded_writer.set(true);
+            }
+        });
+
+        // Changes to the Tasks are applied to the tasks signal straight away and then sent to the data source.
+        // A change that the data source rejects is rolled back and shown in an error toast.
+        let mutations = Mutations::new(source, tasks_writer, trash_writer);
+
+        // A signal to store the number of days a Task is kept in the trash before it is purged automatically
+        let (trash_retention_days, trash_retention_days_writer) = signal(
+            storage::load::<u32>(storage::TRASH_RETENTION_DAYS_KEY).unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+        );
+
+        // A signal to store the id of the user who is using the app. Comments are posted as this user, and only this user's comments can be edited.
+        let (current_user, current_user_writer) = signal(storage::load::<UserId>(storage::CURRENT_USER_KEY).unwrap_or_default());
+
+        // Once the users have loaded, the first user is picked if the stored user is no longer in the directory
+        Effect::new(move |_| {
+            users.map(|users| {
+                if users.get(current_user.get_untracked()).is_none() {
+                    if let Some(user) = users.iter().next() {
+                        current_user_writer.set(user.id);
+                    }
+                }
+            });
+        });
+
+        Effect::new(move |_| {
+            current_user.with(|current_user| storage::save(storage::CURRENT_USER_KEY, current_user));
+        });
+
+        // The panel is read from the path of the URL, and is changed by navigating to the path of another panel,
+        // so that the back button and bookmarks open the panel the user had
+        let location = use_location();
+        let panel = Memo::new(move |_| location.pathname.with(|path| Panel::from_path(path)));
+        let navigate = StoredValue::new_local(Box::new(use_navigate()) as Navigate);
+
+        // Purge the expired Tasks from the trash when the trash is loaded, whenever the retention period is changed or
+        // the trash is opened, and every hour while the app stays open
+        let purge_timer = Trigger::new();
+        set_interval(move || purge_timer.notify(), TRASH_PURGE_INTERVAL);
+        let trash_opened = Memo::new(move |_| panel.get() == Panel::Trash());
+
+        Effect::new(move |_| {
+            let retention_days = trash_retention_days.get();
+            purge_timer.track();
+            trash_opened.track();
+
+            storage::save(storage::TRASH_RETENTION_DAYS_KEY, &retention_days);
+
+            if trash_loaded.get() && trash.with_untracked(|trash| has_expired_tasks(trash, retention_days, now())) {
+                purge_expired_tasks(&mut trash_writer.write(), retention_days, now());
+            }
+        });
+
+        // A link to the app opens the view in its query string. Without a view in the link, the TaskList is shown
+        // with the sort the user last had and without filters.
+        let query_params = parse_query(&location.search.get_untracked());
+        let linked_view = TaskView::is_in_query_params(&query_params).then(|| TaskView::from_query_params(&query_params, &LocalTimeZone));
+
+        // A signal to store the order the TaskList is sorted in. The order is kept between sessions.
+        let (sort, sort_writer) = signal(match &linked_view {
+            Some(view) => view.sort.clone(),
+            None => storage::load::<TaskSort>(storage::TASK_SORT_KEY).unwrap_or_default()
+        });
+
+        Effect::new(move |_| {
+            sort.with(|sort| storage::save(storage::TASK_SORT_KEY, sort));
+        });
+
+        // A signal to store the filters of the TaskList
+        let (filter, filter_writer) = signal(linked_view.as_ref().map(|view| view.filter.clone()).unwrap_or_default());
+
+        // A signal to store the text of the search box, and the words that are searched for. The search index is built
+        // when the Tasks are first searched, and is brought up to date with the Tasks on each search after that.
+        let (search, search_writer) = signal(linked_view.map(|view| view.search).unwrap_or_default());
+        let search_query = Memo::new(move |_| search.with(|search| SearchQuery::new(search)));
+        let search_index = StoredValue::new(SearchIndex::default());
+
+        // A signal to store the views the user has saved. The views are kept between sessions.
+        let (saved_views, saved_views_writer) = signal(storage::load::<Vec<SavedView>>(storage::SAVED_VIEWS_KEY).unwrap_or_default());
+
+        Effect::new(move |_| {
+            saved_views.with(|saved_views| storage::save(storage::SAVED_VIEWS_KEY, saved_views));
+        });
+
+        let state = Self {
+            tasks,
+            tasks_resource,
+            tasks_loaded,
+            users,
+            current_user,
+            current_user_writer,
+            panel,
+            navigate,
+            sort,
+            sort_writer,
+            filter,
+            filter_writer,
+            search,
+            search_writer,
+            search_query,
+            search_index,
+            saved_views,
+            saved_views_writer,
+            trash,
+            trash_writer,
+            trash_retention_days,
+            trash_retention_days_writer,
+            mutations
+        };
+
+        // The view is kept in the query string of the URL, so that a link to the page opens the TaskList as the user sees it.
+        // The last query string written by the app is kept, so that a query string changed from outside the app, e.g. when
+        // the back button returns to a URL with another view, can be told apart and opened.
+        let written_query = StoredValue::new(location.search.get_untracked());
+
+        Effect::new(move |_| {
+            let query = format_query(&state.current_view().query_params(&LocalTimeZone));
+            let search = query.trim_start_matches('?').to_string();
+
+            if location.search.with_untracked(|current_search| *current_search != search) {
+                let url = format!("{}{}", location.pathname.get_untracked(), query);
+                written_query.set_value(search);
+                state.navigate.with_value(|navigate| navigate(&url, NavigateOptions { replace : true, scroll : false, ..NavigateOptions::default() }));
+            }
+        });
+
+        Effect::new(move |_| {
+            let search = location.search.get();
+
+            if written_query.with_value(|written_query| *written_query != search) {
+                written_query.set_value(search.clone());
+                state.open_view(TaskView::from_query_params(&parse_query(&search), &LocalTimeZone));
+            }
+        });
+
+        state
+    }
+
+    // Calls f with the Tasks listed in the TaskList, which are the Tasks that match the filter in the order of the sort.
+    // While the Tasks are searched, only the Tasks that match the search are listed, with the best match first.
+    pub fn with_listed_tasks<T>(&self, f : impl FnOnce(&[&Task]) -> T) -> T {
+        self.tasks.with(|tasks| {
+            let filtered_tasks = self.filter.with(|filter| filter.filter(tasks, now(), &LocalTimeZone));
+
+            self.search_query.with(|query| {
+                if query.is_empty() {
+                    return f(&self.sort.with(|sort| sort.sort(filtered_tasks)));
+                }
+
+                let results = self.search_index.try_update_value(|index| {
+                    index.sync(tasks);
+                    index.search(query)
+                }).unwrap_or_default();
+
+                let filtered_tasks = filtered_tasks.into_iter().map(|task| (task.id, task)).collect::<HashMap<_, _>>();
+
+                f(&results.iter().filter_map(|result| filtered_tasks.get(&result.task_id).copied()).collect::<Vec<_>>())
+            })
+        })
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search_query.with(|query| !query.is_empty())
+    }
+
+    // The Task that is highlighted in the TaskList. A Task stays selected while it is edited.
+    pub fn selected_task_id(&self) -> Option<TaskId> {
+        match self.panel.get() {
+            Panel::Details(task_id) | Panel::EditTask(task_id) => Some(task_id),
+            _ => None
+        }
+    }
+
+    // Navigates to the URL of the panel. The query string of the current view is kept, so that the TaskList is unchanged.
+    // Replacing the current URL instead of adding one leaves the browser history as it was.
+    fn open_panel(&self, panel : Panel, replace : bool) {
+        let url = format!("{}{}", panel.path(), format_query(&untrack(|| self.current_view()).query_params(&LocalTimeZone)));
+        self.navigate.with_value(|navigate| navigate(&url, NavigateOptions { replace, scroll : false, ..NavigateOptions::default() }));
+    }
+
+    // Selecting a Task renders its details in place of the TaskForm or the trash
+    pub fn select_task(&self, task_id : TaskId) {
+        self.open_panel(Panel::Details(task_id), false);
+    }
+
+    // Moves the selection with the keyboard. The URL is replaced, so that the back button does not go through every row on the way.
+    pub fn move_selection(&self, task_id : TaskId) {
+        self.open_panel(Panel::Details(task_id), true);
+    }
+
+    // Opens the TaskForm empty to create a new Task, and clears the selection
+    pub fn open_new_task_form(&self) {
+        self.open_panel(Panel::NewTask(), false);
+    }
+
+    // Opens the TaskForm pre-filled with the Task. The Task stays selected in the TaskList.
+    pub fn open_edit_task_form(&self, task_id : TaskId) {
+        self.open_panel(Panel::EditTask(task_id), false);
+    }
+
+    // Closes the TaskForm. The details of an edited Task are rendered again. The URL of the form is replaced,
+    // so that the back button does not open the form again.
+    pub fn close_task_form(&self) {
+        let panel = match self.panel.get_untracked() {
+            Panel::EditTask(task_id) => Panel::Details(task_id),
+            _ => Panel::Empty()
+        };
+
+        self.open_panel(panel, true);
+    }
+
+    // The trash is rendered in place of the TaskForm and TaskDetails, and the selection is cleared
+    pub fn open_trash(&self) {
+        self.open_panel(Panel::Trash(), false);
+    }
+
+    // Sorts the TaskList by the column alone, or reverses the direction if it is already sorted by the column
+    pub fn sort_by(&self, column : SortColumn) {
+        self.sort_writer.update(|sort| sort.sort_by(column));
+    }
+
+    // Sorts the TaskList by the column after the columns it is already sorted by
+    pub fn then_by(&self, column : SortColumn) {
+        self.sort_writer.update(|sort| sort.then_by(column));
+    }
+
+    pub fn toggle_status_filter(&self, status : TaskStatus) {
+        self.filter_writer.update(|filter| toggle(&mut filter.statuses, status));
+    }
+
+    pub fn toggle_priority_filter(&self, priority : TaskPriority) {
+        self.filter_writer.update(|filter| toggle(&mut filter.priorities, priority));
+    }
+
+    pub fn toggle_assignee_filter(&self, user_id : UserId) {
+        self.filter_writer.update(|filter| toggle(&mut filter.assignees, user_id));
+    }
+
+    pub fn set_due_filter(&self, due : Option<DueRange>) {
+        self.filter_writer.update(|filter| filter.due = due);
+    }
+
+    pub fn clear_filter(&self) {
+        self.filter_writer.set(TaskFilter::default());
+    }
+
+    pub fn set_search(&self, search : String) {
+        self.search_writer.set(search);
+    }
+
+    // The filter, sort and search the TaskList is shown with
+    pub fn current_view(&self) -> TaskView {
+        TaskView { filter : self.filter.get(), sort : self.sort.get(), search : self.search.get() }
+    }
+
+    // Shows the TaskList with the filter, sort and search of the view
+    pub fn open_view(&self, view : TaskView) {
+        self.filter_writer.set(view.filter);
+        self.sort_writer.set(view.sort);
+        self.search_writer.set(view.search);
+    }
+
+    // Saves the current view under the name, replacing a saved view with the same name
+    pub fn save_current_view(&self, name : &str) {
+        let view = untrack(|| self.current_view());
+        self.saved_views_writer.update(|saved_views| save_view(saved_views, name, view));
+    }
+
+    pub fn delete_saved_view(&self, name : &str) {
+        self.saved_views_writer.update(|saved_views| saved_views.retain(|saved_view| saved_view.name != name));
+    }
+
+    // Moves the Task to the trash. The selection is cleared if the Task was selected.
+    pub fn move_task_to_trash(&self, task_id : TaskId) {
+        self.mutations.move_task_to_trash(task_id);
+
+        if self.panel.with_untracked(|panel| matches!(panel, Panel::Details(id) | Panel::EditTask(id) if *id == task_id)) {
+            self.open_panel(Panel::Empty(), true);
+        }
+    }
+
+    // Removes the Task from the trash without restoring it. The Task was deleted from the data source when it was moved
+    // to the trash, so it only has to be removed from the trash kept by the app.
+    pub fn delete_task_forever(&self, task_id : TaskId) {
+        self.trash_writer.write().retain(|trashed_task| trashed_task.task.id != task_id);
+    }
+
+    // Removes every Task from the trash. Like delete_task_forever, the data source is not changed.
+    pub fn empty_trash(&self) {
+        self.trash_writer.write().clear();
+    }
+
+    pub fn set_trash_retention_days(&self, days : u32) {
+        self.trash_retention_days_writer.set(days.max(1));
+    }
+
+    pub fn set_current_user(&self, user_id : UserId) {
+        self.current_user_writer.set(user_id);
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "local-data")]
+use todo_core::model::TaskId;
+use todo_core::model::{TrashedTask, UserDirectory};
+use todo_core::stored::StoredTrashedTask;
+
+// Key used by the LocalDataSource to store the list of Tasks in localStorage
+#[cfg(feature = "local-data")]
+pub const TASKS_KEY: &str = "tasks";
+
+// Key used to store the list of trashed Tasks in localStorage
+pub const TRASH_KEY: &str = "trash";
+
+// Key used to store the number of days trashed Tasks are kept before they are purged
+pub const TRASH_RETENTION_DAYS_KEY: &str = "trash_retention_days";
+
+// Key used by the LocalDataSource to store the last id given to a Task
+#[cfg(feature = "local-data")]
+pub const TASK_ID_SEQUENCE_KEY: &str = "task_id_sequence";
+
+// Key used to store the user who is using the app
+pub const CURRENT_USER_KEY: &str = "current_user";
+
+// Key used to store the order the TaskList is sorted in
+pub const TASK_SORT_KEY: &str = "task_sort";
+
+// Key used to store the views the user has saved
+pub const SAVED_VIEWS_KEY: &str = "saved_views";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// Reads the value stored under the key and deserializes it.
+// Returns None if nothing has been stored yet or if the stored value can not be deserialized.
+pub fn load<T: DeserializeOwned>(key : &str) -> Option<T> {
+    let json = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+// Reads the trashed Tasks stored under the key. Tasks stored by earlier versions of the app are migrated to the model,
+// with the users they stored by name found in the directory.
+pub fn load_trash(key : &str, users : &UserDirectory) -> Vec<TrashedTask> {
+    let stored_trash = load::<Vec<StoredTrashedTask>>(key).unwrap_or_default();
+    stored_trash.into_iter().map(|stored_trashed_task| stored_trashed_task.into_trashed_task(users)).collect()
+}
+
+// Serializes the value and stores it under the key, replacing any previously stored value.
+pub fn save<T: Serialize>(key : &str, value : &T) {
+    if let (Some(storage), Ok(json)) = (local_storage(), serde_json::to_string(value)) {
+        let _ = storage.set_item(key, &json);
+    }
+}
+
+// Returns a new Task id from the sequence stored in localStorage. The sequence is read and incremented on every call,
+// so ids are not reused after Tasks are deleted or the app is reloaded, and tabs that create Tasks see each other's ids.
+#[cfg(feature = "local-data")]
+pub fn next_task_id() -> TaskId {
+    let id = load::<u32>(TASK_ID_SEQUENCE_KEY).unwrap_or(0) + 1;
+    save(TASK_ID_SEQUENCE_KEY, &id);
+    TaskId(id)
+}
+
+// Moves the sequence past the given id, if it is not already past it
+#[cfg(feature = "local-data")]
+pub fn advance_task_id_sequence(TaskId(id) : TaskId) {
+    if load::<u32>(TASK_ID_SEQUENCE_KEY).unwrap_or(0) < id {
+        save(TASK_ID_SEQUENCE_KEY, &id);
+    }
+}
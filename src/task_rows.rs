@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+
+use leptos::prelude::*;
+use todo_core::model::{Task, TaskId};
+
+// The signals a single TaskList row is rendered from. The signals are reference counted, so a row keeps them for as long
+// as it is rendered, regardless of the scope the row was created in.
+#[derive(Clone)]
+pub struct TaskRow {
+    pub task_id : TaskId,
+    pub task : ArcRwSignal<Task>,
+    pub is_selected : ArcRwSignal<bool>,
+    pub is_pending : ArcRwSignal<bool>
+}
+
+// Keeps a TaskRow for each Task in the list. When the Tasks, the selection or the pending Tasks change, only the signals of the
+// rows that are affected are set, so editing one Task or selecting a row updates those rows instead of rebuilding the whole list.
+// Rows are keyed by the Task id, and the ids signal only changes when a Task is added, removed or moved.
+#[derive(Clone, Copy)]
+pub struct TaskRows {
+    rows : StoredValue<HashMap<TaskId, TaskRow>>,
+    ids : RwSignal<Vec<TaskId>>,
+    selected_task_id : StoredValue<Option<TaskId>>,
+    pending_task_ids : StoredValue<HashSet<TaskId>>
+}
+
+impl TaskRows {
+    // Creates the store without rows. The rows are added by sync.
+    pub fn new(selected_task_id : Option<TaskId>) -> Self {
+        Self {
+            rows : StoredValue::new(HashMap::new()),
+            ids : RwSignal::new(Vec::new()),
+            selected_task_id : StoredValue::new(selected_task_id),
+            pending_task_ids : StoredValue::new(HashSet::new())
+        }
+    }
+
+    // The ids of the Tasks in the order they are listed
+    pub fn ids(&self) -> ReadSignal<Vec<TaskId>> {
+        self.ids.read_only()
+    }
+
+    pub fn row(&self, task_id : TaskId) -> Option<TaskRow> {
+        self.rows.with_value(|rows| rows.get(&task_id).cloned())
+    }
+
+    // Brings the rows up to date with the Tasks, which are listed in the given order. Rows are added for new Tasks and removed
+    // for Tasks that are no longer listed, and the task signal of a row is only set if its Task has changed.
+    pub fn sync(&self, tasks : &[&Task]) {
+        let selected_task_id = self.selected_task_id.get_value();
+        let pending_task_ids = self.pending_task_ids.get_value();
+        let mut changed_rows = Vec::new();
+
+        self.rows.update_value(|rows| {
+            for task in tasks {
+                match rows.get(&task.id) {
+                    Some(row) => {
+                        if row.task.with_untracked(|row_task| row_task != *task) {
+                            changed_rows.push((row.task.clone(), (*task).clone()));
+                        }
+                    },
+                    None => {
+                        rows.insert(task.id, TaskRow {
+                            task_id : task.id,
+                            task : ArcRwSignal::new((*task).clone()),
+                            is_selected : ArcRwSignal::new(selected_task_id == Some(task.id)),
+                            is_pending : ArcRwSignal::new(pending_task_ids.contains(&task.id))
+                        });
+                    }
+                }
+            }
+
+            if rows.len() != tasks.len() {
+                let task_ids = tasks.iter().map(|task| task.id).collect::<HashSet<_>>();
+                rows.retain(|task_id, _| task_ids.contains(task_id));
+            }
+        });
+
+        // The signals are set once the rows are no longer borrowed, since setting them notifies the rendered rows
+        for (row_task, task) in changed_rows {
+            row_task.set(task);
+        }
+
+        let ids = tasks.iter().map(|task| task.id).collect::<Vec<_>>();
+
+        if self.ids.with_untracked(|current_ids| *current_ids != ids) {
+            self.ids.set(ids);
+        }
+    }
+
+    // Moves the selection to the Task. Only the row that was selected and the row that is selected are notified.
+    pub fn select(&self, task_id : Option<TaskId>) {
+        let previous_task_id = self.selected_task_id.get_value();
+
+        if previous_task_id == task_id {
+            return;
+        }
+
+        self.selected_task_id.set_value(task_id);
+
+        for (task_id, is_selected) in [(previous_task_id, false), (task_id, true)] {
+            if let Some(row) = task_id.and_then(|task_id| self.row(task_id)) {
+                row.is_selected.set(is_selected);
+            }
+        }
+    }
+
+    // Marks the rows of the Tasks with changes that are waiting for the data source. Only the rows that have become pending
+    // or are no longer pending are notified.
+    pub fn set_pending(&self, task_ids : HashSet<TaskId>) {
+        let previous_task_ids = self.pending_task_ids.get_value();
+
+        for task_id in previous_task_ids.symmetric_difference(&task_ids) {
+            if let Some(row) = self.row(*task_id) {
+                row.is_pending.set(task_ids.contains(task_id));
+            }
+        }
+
+        self.pending_task_ids.set_value(task_ids);
+    }
+}